@@ -0,0 +1,30 @@
+/// A boolean flag that only changes once the underlying value has been stable for a number of
+/// frames. The game occasionally gets read in the middle of writing some of its flags, which would
+/// otherwise show up as a transition lasting a single tick.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Debounced<const FRAMES: u32> {
+    value: bool,
+    candidate: bool,
+    candidate_since: u32,
+}
+
+impl<const FRAMES: u32> Debounced<FRAMES> {
+    /// Feeds a new raw reading, taken at the given value of the game's frame counter
+    pub const fn update(self, raw: bool, frame: u32) -> Self {
+        if raw != self.candidate {
+            Self {
+                candidate: raw,
+                candidate_since: frame,
+                ..self
+            }
+        } else if frame.wrapping_sub(self.candidate_since) >= FRAMES {
+            Self { value: raw, ..self }
+        } else {
+            self
+        }
+    }
+
+    pub const fn value(&self) -> bool {
+        self.value
+    }
+}
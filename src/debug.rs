@@ -0,0 +1,317 @@
+use crate::{
+    game_state::{GameState, Levels, LAST_ZONE},
+    save_slots::{EXTRA_ENTRIES, SAVE_SLOTS},
+};
+use asr::{time::Duration, time_util::Instant, timer};
+
+/// Formats a message into a fixed size buffer and sends it to the runtime's log.
+/// Messages that don't fit in the buffer get truncated.
+macro_rules! log {
+    ($($arg:tt)*) => {{
+        let mut buf = arrayvec::ArrayString::<256>::new();
+        let _ = core::fmt::Write::write_fmt(&mut buf, format_args!($($arg)*));
+        asr::print_message(&buf);
+    }};
+}
+pub(crate) use log;
+
+/// Formats a value into a fixed size buffer and publishes it as a timer variable
+macro_rules! set_variable {
+    ($key:expr, $($arg:tt)*) => {{
+        let mut buf = arrayvec::ArrayString::<128>::new();
+        let _ = core::fmt::Write::write_fmt(&mut buf, format_args!($($arg)*));
+        asr::timer::set_variable($key, &buf);
+    }};
+}
+pub(crate) use set_variable;
+
+macro_rules! log_changes {
+    ($old:ident, $current:ident, $($field:ident),* $(,)?) => {
+        $(
+            if $old.$field != $current.$field {
+                log!(
+                    concat!(stringify!($field), ": {:?} -> {:?}"),
+                    $old.$field,
+                    $current.$field
+                );
+            }
+        )*
+    };
+}
+
+/// Logs every value of the game state that changed since the previous tick
+pub fn log_transitions(old: &GameState, current: &GameState) {
+    log_changes!(
+        old,
+        current,
+        level,
+        state,
+        end_of_level_flag,
+        game_ending_flag,
+        time_bonus,
+        perfect_bonus,
+        save_select,
+        zone_select,
+        save_slot,
+        emeralds,
+        screen_faded_out,
+        character,
+        control_locked,
+        form,
+        debug_mode,
+        competition_mode,
+        boss_flag,
+        rings,
+        lives,
+        level_timer,
+        game_mode,
+        chaos_emeralds,
+        super_emeralds,
+        score,
+        paused,
+        player_mode,
+    );
+}
+
+/// Logs a timer decision on a single line of `key=value` pairs, with what caused it and where the
+/// game was at the time, so decisions can be searched for and compared across reports
+pub fn log_decision(decision: &str, reason: impl core::fmt::Debug, current: &GameState) {
+    log!(
+        "decision={} reason={:?} frame={} level={:?} state={:#04X} game_mode={:#04X} save_select={}",
+        decision,
+        reason,
+        current.frame_counter,
+        current.level,
+        current.state,
+        current.game_mode,
+        current.save_select
+    );
+}
+
+macro_rules! check {
+    ($issues:ident, $current:ident.$field:ident, $valid:expr) => {
+        let valid: fn(_) -> bool = $valid;
+        if valid($current.$field) {
+            log!(
+                concat!("Self-check: ", stringify!($field), " = {:?}"),
+                $current.$field
+            );
+        } else {
+            log!(
+                concat!(
+                    "Self-check: ",
+                    stringify!($field),
+                    " = {:?} is out of range"
+                ),
+                $current.$field
+            );
+            $issues += 1;
+        }
+    };
+}
+
+/// Reads every watched value once and validates it against the range the game can produce, so a
+/// setup that doesn't split can be diagnosed from the log and a single variable
+pub fn self_check(current: &GameState, failed_reads: u32) {
+    let mut issues = 0;
+
+    check!(issues, current.game_mode, |mode| mode & 0x7F <= 0x50
+        && mode & 3 == 0);
+    check!(issues, current.zone, |zone| zone <= LAST_ZONE);
+    check!(issues, current.save_select, |slot| slot as u32
+        <= SAVE_SLOTS + EXTRA_ENTRIES);
+    check!(issues, current.save_slot, |state| state <= 3
+        || state == 0x80);
+    check!(issues, current.emeralds, |emeralds| emeralds <= 14);
+    check!(issues, current.rings, |rings| rings <= 999);
+    check!(issues, current.lives, |lives| lives <= 99);
+    check!(issues, current.level_timer, |frames| frames < 10 * 60 * 60);
+    check!(issues, current.time_bonus, |bonus| bonus <= 50000);
+    check!(issues, current.perfect_bonus, |bonus| bonus <= 50000);
+
+    // These are decoded into one of their known values whatever the game holds, so there's nothing
+    // to validate, but they're still worth having in the log
+    log!(
+        "Self-check: level = {:?}, character = {:?}, form = {:?}",
+        current.level,
+        current.character,
+        current.form
+    );
+
+    // Every read failing means the game's memory isn't accessible at all, rather than misread
+    if failed_reads != 0 {
+        log!("Self-check: {} reads failed", failed_reads);
+        issues += 1;
+    }
+
+    if issues == 0 {
+        timer::set_variable("Self-check", "OK");
+    } else {
+        set_variable!("Self-check", "{} issue(s), see the log", issues);
+    }
+}
+
+/// Number of transitions kept in the history
+const HISTORY_LEN: usize = 16;
+
+#[derive(Clone, Copy)]
+struct Transition {
+    frame: u32,
+    level: Levels,
+    state: u8,
+    end_of_level_flag: bool,
+    game_ending_flag: bool,
+}
+
+impl Transition {
+    fn log(&self) {
+        log!(
+            "frame {}: {:?}, state {:#04X}, end of level {}, game ending {}",
+            self.frame,
+            self.level,
+            self.state,
+            self.end_of_level_flag,
+            self.game_ending_flag
+        );
+    }
+}
+
+/// Ring buffer of the most recent significant transitions (level, state byte and flags), so the
+/// sequence leading to a desync can be inspected after the fact.
+pub struct TransitionHistory {
+    entries: [Option<Transition>; HISTORY_LEN],
+    next: usize,
+}
+
+impl TransitionHistory {
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; HISTORY_LEN],
+            next: 0,
+        }
+    }
+
+    /// Keeps every transition for a later dump, and publishes the latest one when asked to
+    pub fn record(&mut self, old: &GameState, current: &GameState, publish: bool) {
+        if old.level == current.level
+            && old.state == current.state
+            && old.end_of_level_flag == current.end_of_level_flag
+            && old.game_ending_flag == current.game_ending_flag
+        {
+            return;
+        }
+
+        let transition = Transition {
+            frame: current.frame_counter,
+            level: current.level,
+            state: current.state,
+            end_of_level_flag: current.end_of_level_flag,
+            game_ending_flag: current.game_ending_flag,
+        };
+
+        if publish {
+            set_variable!(
+                "Last transition",
+                "{:?} ({:#04X}) @ {}",
+                transition.level,
+                transition.state,
+                transition.frame
+            );
+        }
+
+        self.entries[self.next] = Some(transition);
+        self.next = (self.next + 1) % HISTORY_LEN;
+    }
+
+    /// Logs the whole history, oldest transition first
+    pub fn dump(&self) {
+        log!("Transition history (oldest first):");
+        self.entries[self.next..]
+            .iter()
+            .chain(&self.entries[..self.next])
+            .flatten()
+            .for_each(Transition::log);
+    }
+}
+
+/// A second of wall clock time, over which the rates below are measured. The runtime's own ticks
+/// can't be the clock, as they're what stalls when the host is under load.
+pub struct SecondWindow {
+    start: Option<Instant>,
+}
+
+impl SecondWindow {
+    pub const fn new() -> Self {
+        Self { start: None }
+    }
+
+    /// Once the window has lasted a second or more, returns how long it lasted in milliseconds and
+    /// starts the next one
+    pub fn close(&mut self) -> Option<u32> {
+        let now = Instant::now();
+        let start = *self.start.get_or_insert(now);
+        let elapsed = now.duration_since(start);
+        if elapsed < Duration::SECOND {
+            return None;
+        }
+        self.start = Some(now);
+        Some(elapsed.whole_milliseconds() as u32)
+    }
+}
+
+/// Counts how many update ticks run per second of wall clock time, so stalls of the autosplitting
+/// runtime can be told apart from the game freezing
+pub struct Heartbeat {
+    ticks: u32,
+    window: SecondWindow,
+}
+
+impl Heartbeat {
+    pub const fn new() -> Self {
+        Self {
+            ticks: 0,
+            window: SecondWindow::new(),
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+
+        if let Some(milliseconds) = self.window.close() {
+            timer::set_variable_int("Ticks per second", self.ticks * 1000 / milliseconds);
+            self.ticks = 0;
+        }
+    }
+}
+
+/// Tick rate of the autosplitting runtime, unless the runtime is stalling
+pub const NOMINAL_TICK_RATE: u32 = 120;
+
+/// Counts the memory reads that failed per second of wall clock time. A steady stream of failures
+/// points to the process not being accessible, while zero failures and wrong values point to bad
+/// offsets instead.
+pub struct ReadFailures {
+    failures: u32,
+    window: SecondWindow,
+}
+
+impl ReadFailures {
+    pub const fn new() -> Self {
+        Self {
+            failures: 0,
+            window: SecondWindow::new(),
+        }
+    }
+
+    pub fn tick(&mut self, failures: u32) {
+        self.failures += failures;
+
+        if let Some(milliseconds) = self.window.close() {
+            timer::set_variable_int(
+                "Failed reads per second",
+                self.failures * 1000 / milliseconds,
+            );
+            self.failures = 0;
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::debug::log;
+use asr::{time::Duration, timer, timer::TimerState};
+
+/// Forwards the start, split and reset decisions to the timer. In dry-run mode the timer is left
+/// untouched: every decision is logged instead and the timer state is simulated, so the triggers
+/// can be validated against a real run.
+pub struct TimerDriver {
+    dry_run: bool,
+    simulated_state: TimerState,
+    game_time_paused: bool,
+    timer_paused: bool,
+}
+
+impl TimerDriver {
+    pub const fn new() -> Self {
+        Self {
+            dry_run: false,
+            simulated_state: TimerState::NotRunning,
+            game_time_paused: false,
+            timer_paused: false,
+        }
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        if self.dry_run != dry_run {
+            self.dry_run = dry_run;
+            self.simulated_state = TimerState::NotRunning;
+        }
+    }
+
+    pub fn state(&self) -> TimerState {
+        if self.dry_run {
+            self.simulated_state
+        } else {
+            timer::state()
+        }
+    }
+
+    /// State of the actual timer, which a dry run leaves alone. The run being timed lives on
+    /// through a dry run switched on midway, so whatever belongs to the run follows this one.
+    pub fn real_state(&self) -> TimerState {
+        timer::state()
+    }
+
+    pub fn start(&mut self) {
+        self.game_time_paused = false;
+        self.timer_paused = false;
+        if self.dry_run {
+            log!("[Dry run] Would start the timer");
+            self.simulated_state = TimerState::Running;
+        } else {
+            timer::start();
+        }
+    }
+
+    pub fn split(&mut self) {
+        if self.dry_run {
+            log!("[Dry run] Would split");
+        } else {
+            timer::split();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.game_time_paused = false;
+        self.timer_paused = false;
+        if self.dry_run {
+            log!("[Dry run] Would reset the timer");
+            self.simulated_state = TimerState::NotRunning;
+        } else {
+            timer::reset();
+        }
+    }
+
+    /// Pauses or resumes game time, only notifying the timer when the state actually changes
+    pub fn set_game_time_paused(&mut self, paused: bool) {
+        if self.game_time_paused == paused {
+            return;
+        }
+        self.game_time_paused = paused;
+
+        if self.dry_run {
+            if paused {
+                log!("[Dry run] Would pause game time");
+            } else {
+                log!("[Dry run] Would resume game time");
+            }
+        } else if paused {
+            timer::pause_game_time();
+        } else {
+            timer::resume_game_time();
+        }
+    }
+
+    /// Pauses or resumes the whole timer. Only a pause made from here gets resumed, so a pause the
+    /// runner made themselves is left alone.
+    pub fn set_timer_paused(&mut self, paused: bool) {
+        let expected = if paused {
+            TimerState::Running
+        } else {
+            TimerState::Paused
+        };
+        if self.timer_paused == paused || self.state() != expected {
+            return;
+        }
+        self.timer_paused = paused;
+
+        if self.dry_run {
+            if paused {
+                log!("[Dry run] Would pause the timer");
+                self.simulated_state = TimerState::Paused;
+            } else {
+                log!("[Dry run] Would resume the timer");
+                self.simulated_state = TimerState::Running;
+            }
+        } else if paused {
+            timer::pause();
+        } else {
+            timer::resume();
+        }
+    }
+
+    /// Overrides game time with a time read from the game. Game time is kept paused so the timer
+    /// doesn't advance it on its own in between two updates. Nothing gets logged in dry-run mode,
+    /// as this happens on every tick.
+    pub fn set_game_time(&mut self, time: Duration) {
+        self.set_game_time_paused(true);
+        if !self.dry_run {
+            timer::set_game_time(time);
+        }
+    }
+}
@@ -0,0 +1,662 @@
+use crate::{debounce::Debounced, debug::set_variable, process::Memory, save_slots};
+use arrayvec::ArrayString;
+use asr::{time::Duration, timer};
+use core::fmt::{self, Write};
+
+/// Snapshot of the game's memory, built once per tick. All the start, split and reset decisions
+/// are made by comparing two consecutive snapshots.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct GameState {
+    pub level: Levels,
+    pub state: u8,
+    pub end_of_level_flag: bool,
+    pub game_ending_flag: bool,
+    pub time_bonus: u16,
+    pub perfect_bonus: u16,
+    pub save_select: u8,
+    pub zone_select: u8,
+    pub save_slot: u8,
+    pub emeralds: u8,
+    pub frame_counter: u32,
+    pub screen_faded_out: bool,
+    pub character: Character,
+    pub control_locked: bool,
+    pub form: Form,
+    pub debug_mode: bool,
+    pub competition_mode: bool,
+    pub boss_flag: bool,
+    pub rings: u16,
+    pub lives: u8,
+    pub level_timer: u32,
+    pub game_mode: u8,
+    pub zone: u8,
+    pub pal: bool,
+    pub chaos_emeralds: u8,
+    pub super_emeralds: u8,
+    pub score: u32,
+    pub paused: bool,
+    pub player_mode: u16,
+    end_of_level_debounce: Debounced<FLAG_STABLE_FRAMES>,
+    game_ending_debounce: Debounced<FLAG_STABLE_FRAMES>,
+}
+
+/// Frames the end of level and game ending flags need to hold a value before it's acted upon
+const FLAG_STABLE_FRAMES: u32 = 2;
+
+/// The in-game timer always counts 60 frames per second, whatever the simulation rate
+pub const TIMER_FRAME_RATE: u32 = 60;
+
+/// Last zone used by the unmodded game, the arena of the final Death Egg boss
+pub const LAST_ZONE: u8 = 0x17;
+
+/// Converts a time counted by the in-game timer to a duration
+pub fn timer_frames_to_duration(frames: u32) -> Duration {
+    Duration::nanoseconds(frames as i64 * 1_000_000_000 / TIMER_FRAME_RATE as i64)
+}
+
+impl GameState {
+    /// Reads a new snapshot from the game. Some of the values are filtered and, depending on
+    /// the game's state, retain what was read on the previous tick.
+    pub fn read(memory: &(impl Memory + ?Sized), previous: &GameState) -> Self {
+        // Filtered state variables. They essentially exclude State.InGame
+        // Used in order to fix a couple of bugs that will otherwise appear with the start trigger
+        let mut state = previous.state;
+        let mut save_slot = previous.save_slot;
+        let save_select = memory.read::<u8>(0xEF4B).unwrap_or_default();
+        let cstate = memory.read::<u8>(0xF600).unwrap_or_default();
+
+        if cstate != STATE_INGAME {
+            state = cstate;
+
+            if save_slots::is_save_slot(save_select) {
+                save_slot = memory
+                    .read::<u8>(save_slots::state_address(save_select as u32))
+                    .unwrap_or_default();
+            }
+        }
+
+        let mut zone_select = previous.zone_select;
+
+        if save_slots::is_save_slot(save_select) {
+            zone_select = memory
+                .read::<u8>(save_slots::zone_address(save_select as u32))
+                .unwrap_or_default();
+        }
+
+        // Define current Act
+        // As act = 0 can both mean Angel Island Act 1 and main menu, we need to check if the LevelStarted flag is set.
+        // If it's not, keep the old value (old.act) in order to allow splitting after returning to the main menu.
+        let act = previous.level;
+
+        let temp_act = memory.read::<u8>(0xEE4F).unwrap_or_default();
+        let temp_zone = memory.read::<u8>(0xEE4E).unwrap_or_default();
+
+        let player_mode = memory.read::<u16>(0xFF08).unwrap_or_default();
+        let character = Character::from_player_mode(player_mode);
+
+        // Widened first, zone IDs past 25 would overflow a byte
+        let level = match temp_act as u16 + temp_zone as u16 * 10 {
+            0 => {
+                // Knuckles skips Sonic's opening cutscene and is dropped straight into the act, so the
+                // LevelStarted flag may still be clear while he's already playing. Being in game is
+                // enough to tell the act apart from the main menu in his case.
+                if memory.read::<u8>(0xF711).unwrap_or_default() != 0
+                    || (character == Character::Knuckles && cstate == STATE_INGAME)
+                {
+                    Levels::AngelIslandAct1
+                } else {
+                    act
+                }
+            }
+            1 => Levels::AngelIslandAct2,
+            10 => Levels::HydrocityAct1,
+            11 => Levels::HydrocityAct2,
+            20 => Levels::MarbleGardenAct1,
+            21 => Levels::MarbleGardenAct2,
+            30 => Levels::CarnivalNightAct1,
+            31 => Levels::CarnivalNightAct2,
+            50 => Levels::IceCapAct1,
+            51 => Levels::IceCapAct2,
+            60 => Levels::LaunchBaseAct1,
+            61 => Levels::LaunchBaseAct2,
+            70 => Levels::MushroomHillAct1,
+            71 => Levels::MushroomHillAct2,
+            40 => Levels::FlyingBatteryAct1,
+            41 => Levels::FlyingBatteryAct2,
+            80 => Levels::SandopolisAct1,
+            81 => Levels::SandopolisAct2,
+            90 => Levels::LavaReefAct1,
+            91 | 220 => Levels::LavaReefAct2,
+            221 => Levels::HiddenPalace,
+            100 | 101 => Levels::SkySanctuary,
+            110 => Levels::DeathEggAct1,
+            111 | 230 => Levels::DeathEggAct2,
+            120 => Levels::DoomsDay,
+            131 => Levels::Ending,
+            _ => act,
+        };
+
+        let frame_counter = memory.read::<u32>(0xFE0C).unwrap_or_default();
+        let end_of_level_debounce = previous.end_of_level_debounce.update(
+            memory.read::<u8>(0xFAA8).unwrap_or_default() != 0,
+            frame_counter,
+        );
+        let game_ending_debounce = previous.game_ending_debounce.update(
+            memory.read::<u8>(0xEF72).unwrap_or_default() != 0,
+            frame_counter,
+        );
+
+        Self {
+            level,
+            state,
+            end_of_level_flag: end_of_level_debounce.value(),
+            game_ending_flag: game_ending_debounce.value(),
+            time_bonus: memory.read::<u16>(0xF7D2).unwrap_or_default(),
+            perfect_bonus: memory.read::<u16>(0xF7D6).unwrap_or_default(),
+            save_select,
+            zone_select,
+            save_slot,
+            emeralds: memory
+                .read::<u8>(0xFFB0)
+                .unwrap_or_default()
+                .wrapping_add(memory.read::<u8>(0xFFB1).unwrap_or_default()),
+            frame_counter,
+            // Once a fade out completes, the whole palette is black
+            screen_faded_out: memory
+                .read::<[u8; 0x80]>(0xFC00)
+                .is_some_and(|palette| palette.iter().all(|&b| b == 0)),
+            character,
+            control_locked: memory.read::<u8>(0xF7CA).unwrap_or_default() != 0,
+            form: Form::from_flags(
+                memory.read::<u8>(0xFE19).unwrap_or_default(),
+                memory.read::<u8>(0xFE18).unwrap_or_default(),
+            ),
+            debug_mode: memory.read::<u16>(0xFE08).unwrap_or_default() != 0,
+            competition_mode: memory.read::<u8>(0xFFD8).unwrap_or_default() != 0,
+            boss_flag: memory.read::<u8>(0xF7AA).unwrap_or_default() != 0,
+            rings: memory.read::<u16>(0xFE20).unwrap_or_default(),
+            lives: memory.read::<u8>(0xFE12).unwrap_or_default(),
+            // The act timer is stored as minutes, seconds and frames, and converted to frames here.
+            // Read as a whole, so emulators storing memory byte swapped get the fields right too.
+            level_timer: memory.read::<u32>(0xFE22).map_or(0, |timer| {
+                let minutes = timer >> 16 & 0xFF;
+                let seconds = timer >> 8 & 0xFF;
+                let frames = timer & 0xFF;
+                (minutes * 60 + seconds) * 60 + frames
+            }),
+            pal: memory.read::<u8>(0xFFF8).unwrap_or_default() & 0x40 != 0,
+            chaos_emeralds: memory.read::<u8>(0xFFB0).unwrap_or_default(),
+            // Counts the Super Emeralds collected in special stages, whether they've been powered up
+            // at the Hidden Palace shrine yet or not
+            super_emeralds: memory.read::<u8>(0xFFB1).unwrap_or_default(),
+            // The score always ends with a zero, which isn't stored
+            score: memory
+                .read::<u32>(0xFE26)
+                .unwrap_or_default()
+                .saturating_mul(10),
+            paused: memory.read::<u16>(0xF63A).unwrap_or_default() != 0,
+            player_mode,
+            end_of_level_debounce,
+            game_ending_debounce,
+            game_mode: cstate,
+            zone: temp_zone,
+        }
+    }
+
+    /// Frame rate of the console the game runs as: 50 Hz on PAL consoles, 60 Hz otherwise
+    pub const fn nominal_frame_rate(&self) -> u32 {
+        if self.pal {
+            50
+        } else {
+            60
+        }
+    }
+
+    /// The act timer as a duration
+    pub fn level_time(&self) -> Duration {
+        timer_frames_to_duration(self.level_timer)
+    }
+
+    /// Publishes the values of interest as timer variables
+    pub fn publish(&self) {
+        timer::set_variable_int("Frame counter", self.frame_counter);
+        timer::set_variable("Current level", self.level.name());
+        timer::set_variable("Character", self.character.name());
+        timer::set_variable_int("Rings", self.rings);
+        timer::set_variable_int("Lives", self.lives);
+        timer::set_variable_int("Score", self.score);
+        timer::set_variable("Super state", self.form.name());
+        if self.competition_mode {
+            timer::set_variable(
+                "Competition stage",
+                CompetitionStage::from_zone(self.zone).map_or("-", CompetitionStage::name),
+            );
+        }
+
+        let mut json = ArrayString::<1024>::new();
+        if self.write_json(&mut json).is_ok() {
+            timer::set_variable("Game state", &json);
+        }
+    }
+
+    /// Publishes the player's progress under variables namespaced by the player's number, for
+    /// setups following several instances of the game at once
+    pub fn publish_player(&self, player: usize) {
+        let key = |name| {
+            let mut key = ArrayString::<32>::new();
+            let _ = write!(key, "Player {} - {}", player, name);
+            key
+        };
+
+        timer::set_variable(&key("Level"), self.level.name());
+        timer::set_variable_int(&key("Rings"), self.rings);
+        set_variable!(
+            &key("IGT"),
+            "{}:{:02}.{:02}",
+            self.level_timer / 3600,
+            self.level_timer / 60 % 60,
+            self.level_timer % 60 * 100 / 60
+        );
+    }
+
+    /// Serializes the whole snapshot as a compact JSON object, for external tools to consume
+    pub fn write_json(&self, w: &mut impl Write) -> fmt::Result {
+        write!(
+            w,
+            concat!(
+                r#"{{"level":"{:?}","state":{},"end_of_level_flag":{},"game_ending_flag":{},"#,
+                r#""time_bonus":{},"perfect_bonus":{},"save_select":{},"zone_select":{},"#,
+                r#""save_slot":{},"emeralds":{},"frame_counter":{},"screen_faded_out":{},"#,
+                r#""character":"{:?}","control_locked":{},"form":"{:?}","debug_mode":{},"#,
+                r#""competition_mode":{},"boss_flag":{},"rings":{},"lives":{},"#,
+                r#""level_timer":{},"game_mode":{},"zone":{},"pal":{},"#,
+                r#""chaos_emeralds":{},"super_emeralds":{},"score":{},"paused":{},"#,
+                r#""player_mode":{}}}"#,
+            ),
+            self.level,
+            self.state,
+            self.end_of_level_flag,
+            self.game_ending_flag,
+            self.time_bonus,
+            self.perfect_bonus,
+            self.save_select,
+            self.zone_select,
+            self.save_slot,
+            self.emeralds,
+            self.frame_counter,
+            self.screen_faded_out,
+            self.character,
+            self.control_locked,
+            self.form,
+            self.debug_mode,
+            self.competition_mode,
+            self.boss_flag,
+            self.rings,
+            self.lives,
+            self.level_timer,
+            self.game_mode,
+            self.zone,
+            self.pal,
+            self.chaos_emeralds,
+            self.super_emeralds,
+            self.score,
+            self.paused,
+            self.player_mode,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Character {
+    #[default]
+    SonicAndTails,
+    Sonic,
+    Tails,
+    Knuckles,
+}
+
+impl Character {
+    pub const fn from_player_mode(player_mode: u16) -> Self {
+        match player_mode {
+            1 => Self::Sonic,
+            2 => Self::Tails,
+            3 => Self::Knuckles,
+            _ => Self::SonicAndTails,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::SonicAndTails => "Sonic & Tails",
+            Self::Sonic => "Sonic",
+            Self::Tails => "Tails",
+            Self::Knuckles => "Knuckles",
+        }
+    }
+
+    /// Acts of the character's story, in the order they're played. Knuckles skips Flying Battery
+    /// and his story ends in Sky Sanctuary, while the others only go on to Doomsday with every
+    /// Chaos Emerald, the game ending in Death Egg otherwise.
+    pub const fn route(self, emeralds: u8) -> &'static [Levels] {
+        match self {
+            Self::Knuckles => &KNUCKLES_ROUTE,
+            _ if emeralds >= 7 => &Levels::ACTS,
+            _ => Levels::ACTS.split_at(Levels::ACTS.len() - 1).0,
+        }
+    }
+
+    /// Whether the act is part of the character's story
+    pub const fn plays(self, level: Levels, emeralds: u8) -> bool {
+        let route = self.route(emeralds);
+        let mut index = 0;
+        while index < route.len() {
+            if route[index] as u8 == level as u8 {
+                return true;
+            }
+            index += 1;
+        }
+        false
+    }
+
+    /// The act the character's story goes on to after the given one, if any
+    pub fn next_act(self, level: Levels, emeralds: u8) -> Option<Levels> {
+        let route = self.route(emeralds);
+        let index = route.iter().position(|&act| act == level)?;
+        route.get(index + 1).copied()
+    }
+
+    /// The act the character's story ends with
+    pub const fn final_act(self, emeralds: u8) -> Levels {
+        match self.route(emeralds).last() {
+            Some(&level) => level,
+            None => Levels::DoomsDay,
+        }
+    }
+}
+
+/// Knuckles' story, which skips Flying Battery, going from Mushroom Hill straight to Sandopolis
+const KNUCKLES_ROUTE: [Levels; 20] = [
+    Levels::AngelIslandAct1,
+    Levels::AngelIslandAct2,
+    Levels::HydrocityAct1,
+    Levels::HydrocityAct2,
+    Levels::MarbleGardenAct1,
+    Levels::MarbleGardenAct2,
+    Levels::CarnivalNightAct1,
+    Levels::CarnivalNightAct2,
+    Levels::IceCapAct1,
+    Levels::IceCapAct2,
+    Levels::LaunchBaseAct1,
+    Levels::LaunchBaseAct2,
+    Levels::MushroomHillAct1,
+    Levels::MushroomHillAct2,
+    Levels::SandopolisAct1,
+    Levels::SandopolisAct2,
+    Levels::LavaReefAct1,
+    Levels::LavaReefAct2,
+    Levels::HiddenPalace,
+    Levels::SkySanctuary,
+];
+
+/// Transformation the player is currently in
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Form {
+    #[default]
+    Normal,
+    Super,
+    Hyper,
+}
+
+impl Form {
+    const fn from_flags(super_sonic_knuckles: u8, super_tails: u8) -> Self {
+        match super_sonic_knuckles {
+            0 if super_tails != 0 => Self::Super,
+            0 => Self::Normal,
+            0xFF => Self::Hyper,
+            _ => Self::Super,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Super => "Super",
+            Self::Hyper => "Hyper",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub enum Levels {
+    #[default]
+    AngelIslandAct1,
+    AngelIslandAct2,
+    HydrocityAct1,
+    HydrocityAct2,
+    MarbleGardenAct1,
+    MarbleGardenAct2,
+    CarnivalNightAct1,
+    CarnivalNightAct2,
+    IceCapAct1,
+    IceCapAct2,
+    LaunchBaseAct1,
+    LaunchBaseAct2,
+    MushroomHillAct1,
+    MushroomHillAct2,
+    FlyingBatteryAct1,
+    FlyingBatteryAct2,
+    SandopolisAct1,
+    SandopolisAct2,
+    LavaReefAct1,
+    LavaReefAct2,
+    HiddenPalace,
+    SkySanctuary,
+    DeathEggAct1,
+    DeathEggAct2,
+    DoomsDay,
+    Ending,
+}
+
+impl Levels {
+    /// Every act of the game, in the order they're played in a full game run
+    pub const ACTS: [Self; 25] = [
+        Self::AngelIslandAct1,
+        Self::AngelIslandAct2,
+        Self::HydrocityAct1,
+        Self::HydrocityAct2,
+        Self::MarbleGardenAct1,
+        Self::MarbleGardenAct2,
+        Self::CarnivalNightAct1,
+        Self::CarnivalNightAct2,
+        Self::IceCapAct1,
+        Self::IceCapAct2,
+        Self::LaunchBaseAct1,
+        Self::LaunchBaseAct2,
+        Self::MushroomHillAct1,
+        Self::MushroomHillAct2,
+        Self::FlyingBatteryAct1,
+        Self::FlyingBatteryAct2,
+        Self::SandopolisAct1,
+        Self::SandopolisAct2,
+        Self::LavaReefAct1,
+        Self::LavaReefAct2,
+        Self::HiddenPalace,
+        Self::SkySanctuary,
+        Self::DeathEggAct1,
+        Self::DeathEggAct2,
+        Self::DoomsDay,
+    ];
+
+    /// Whether this is the first act of a zone made of two acts
+    pub const fn is_first_act(self) -> bool {
+        matches!(
+            self,
+            Self::AngelIslandAct1
+                | Self::HydrocityAct1
+                | Self::MarbleGardenAct1
+                | Self::CarnivalNightAct1
+                | Self::IceCapAct1
+                | Self::LaunchBaseAct1
+                | Self::MushroomHillAct1
+                | Self::FlyingBatteryAct1
+                | Self::SandopolisAct1
+                | Self::LavaReefAct1
+                | Self::DeathEggAct1
+        )
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AngelIslandAct1 => "Angel Island Zone - Act 1",
+            Self::AngelIslandAct2 => "Angel Island Zone - Act 2",
+            Self::HydrocityAct1 => "Hydrocity Zone - Act 1",
+            Self::HydrocityAct2 => "Hydrocity Zone - Act 2",
+            Self::MarbleGardenAct1 => "Marble Garden Zone - Act 1",
+            Self::MarbleGardenAct2 => "Marble Garden Zone - Act 2",
+            Self::CarnivalNightAct1 => "Carnival Night Zone - Act 1",
+            Self::CarnivalNightAct2 => "Carnival Night Zone - Act 2",
+            Self::IceCapAct1 => "Ice Cap Zone - Act 1",
+            Self::IceCapAct2 => "Ice Cap Zone - Act 2",
+            Self::LaunchBaseAct1 => "Launch Base Zone - Act 1",
+            Self::LaunchBaseAct2 => "Launch Base Zone - Act 2",
+            Self::MushroomHillAct1 => "Mushroom Hill Zone - Act 1",
+            Self::MushroomHillAct2 => "Mushroom Hill Zone - Act 2",
+            Self::FlyingBatteryAct1 => "Flying Battery Zone - Act 1",
+            Self::FlyingBatteryAct2 => "Flying Battery Zone - Act 2",
+            Self::SandopolisAct1 => "Sandopolis Zone - Act 1",
+            Self::SandopolisAct2 => "Sandopolis Zone - Act 2",
+            Self::LavaReefAct1 => "Lava Reef Zone - Act 1",
+            Self::LavaReefAct2 => "Lava Reef Zone - Act 2",
+            Self::HiddenPalace => "Hidden Palace Zone",
+            Self::SkySanctuary => "Sky Sanctuary Zone",
+            Self::DeathEggAct1 => "Death Egg Zone - Act 1",
+            Self::DeathEggAct2 => "Death Egg Zone - Act 2",
+            Self::DoomsDay => "Doomsday Zone",
+            Self::Ending => "Ending",
+        }
+    }
+}
+
+/// Stages of the 2P Competition mode, which use zone IDs of their own past the main game's
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompetitionStage {
+    AzureLake,
+    BalloonPark,
+    ChromeGadget,
+    DesertPalace,
+    EndlessMine,
+}
+
+impl CompetitionStage {
+    pub const fn from_zone(zone: u8) -> Option<Self> {
+        match zone {
+            0x0E => Some(Self::AzureLake),
+            0x0F => Some(Self::BalloonPark),
+            0x10 => Some(Self::DesertPalace),
+            0x11 => Some(Self::ChromeGadget),
+            0x12 => Some(Self::EndlessMine),
+            _ => None,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AzureLake => "Azure Lake",
+            Self::BalloonPark => "Balloon Park",
+            Self::ChromeGadget => "Chrome Gadget",
+            Self::DesertPalace => "Desert Palace",
+            Self::EndlessMine => "Endless Mine",
+        }
+    }
+}
+
+// Consts used in the script
+pub const STATE_TITLE: u8 = 0x04;
+pub const STATE_DEMO: u8 = 0x08;
+pub const STATE_CONTINUE: u8 = 0x14;
+pub const STATE_LEVELSELECT: u8 = 0x28;
+pub const STATE_SAVESELECT: u8 = 0x4C;
+pub const STATE_LOADING: u8 = 0x8C;
+pub const STATE_INGAME: u8 = 0x0C;
+pub const STATE_SPECIALSTAGE: u8 = 0x34;
+pub const STATE_EXITINGSPECIALSTAGE: u8 = 0x48;
+pub const STATE_COMPETITIONMENU: u8 = 0x38;
+pub const STATE_COMPETITIONRESULTS: u8 = 0x44;
+pub const SAVESLOTSTATE_NEWGAME: u8 = 0x80;
+pub const SAVESLOTSTATE_INPROGRESS: u8 = 0x00;
+pub const SAVESLOTSTATE_COMPLETE: u8 = 0x01;
+pub const SAVESLOTSTATE_COMPLETEWITHEMERALDS: u8 = 0x02;
+pub const SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS: u8 = 0x03;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{process::Wram, settings::Settings};
+    use asr::settings::Gui;
+
+    #[test]
+    fn reads_a_level_in_progress() {
+        let mut wram = Wram::new();
+        wram.write(0xF600, &[STATE_INGAME])
+            .write(0xEE4E, &[1, 1])
+            .write(0xFF08, &3u16.to_be_bytes())
+            .write(0xFE12, &[5])
+            .write(0xFE20, &123u16.to_be_bytes())
+            .write(0xFE22, &[0, 1, 30, 15])
+            .write(0xFE26, &4321u32.to_be_bytes());
+
+        let state = GameState::read(&wram, &GameState::default());
+        assert_eq!(state.level, Levels::HydrocityAct2);
+        assert_eq!(state.game_mode, STATE_INGAME);
+        assert_eq!(state.character, Character::Knuckles);
+        assert_eq!(state.lives, 5);
+        assert_eq!(state.rings, 123);
+        assert_eq!(state.level_timer, (60 + 30) * 60 + 15);
+        assert_eq!(state.score, 43210);
+    }
+
+    #[test]
+    fn keeps_the_level_on_out_of_range_zones() {
+        let mut wram = Wram::new();
+        wram.write(0xF600, &[STATE_INGAME])
+            .write(0xEE4E, &[0xFF, 0xFF]);
+        let previous = GameState {
+            level: Levels::LavaReefAct1,
+            ..Default::default()
+        };
+
+        let state = GameState::read(&wram, &previous);
+        assert_eq!(state.level, Levels::LavaReefAct1);
+    }
+
+    #[test]
+    fn knuckles_skips_flying_battery() {
+        let knuckles = Character::Knuckles;
+        assert!(!knuckles.plays(Levels::FlyingBatteryAct1, 7));
+        assert!(!knuckles.plays(Levels::FlyingBatteryAct2, 7));
+        assert!(knuckles.plays(Levels::SandopolisAct1, 7));
+        assert_eq!(knuckles.final_act(7), Levels::SkySanctuary);
+        assert!(Character::Sonic.plays(Levels::FlyingBatteryAct1, 0));
+        assert_eq!(Character::Sonic.final_act(0), Levels::DeathEggAct2);
+        assert_eq!(Character::Sonic.final_act(7), Levels::DoomsDay);
+
+        let mut settings = Settings::register();
+        settings.character_presets = true;
+        assert!(!settings.act_split_enabled_for(Levels::FlyingBatteryAct2, knuckles, 0));
+        assert!(settings.act_split_enabled_for(Levels::FlyingBatteryAct2, Character::Tails, 0));
+    }
+
+    #[test]
+    fn keeps_the_level_in_the_menus() {
+        let mut wram = Wram::new();
+        wram.write(0xF600, &[STATE_SAVESELECT]);
+        let previous = GameState {
+            level: Levels::IceCapAct1,
+            ..Default::default()
+        };
+
+        let state = GameState::read(&wram, &previous);
+        assert_eq!(state.level, Levels::IceCapAct1);
+        assert_eq!(state.state, STATE_SAVESELECT);
+    }
+}
@@ -1,5 +1,4 @@
 #![no_std]
-#![feature(type_alias_impl_trait, const_async_blocks)]
 #![warn(
     clippy::complexity,
     clippy::correctness,
@@ -9,39 +8,83 @@
     rust_2018_idioms
 )]
 
-use asr::{
-    future::{next_tick, retry},
-    settings::Gui,
-    timer,
-    timer::TimerState,
-    watcher::Watcher,
-    Address, FromEndian, Process,
+mod debounce;
+mod debug;
+mod driver;
+mod game_state;
+mod logic;
+mod process;
+mod save_slots;
+mod second_instance;
+mod settings;
+
+use asr::{future::next_tick, settings::Gui, timer::TimerState};
+use debug::{Heartbeat, ReadFailures, TransitionHistory};
+use driver::TimerDriver;
+use game_state::{GameState, STATE_SAVESELECT};
+use logic::{
+    ActTimes, AnomalyDetector, GameTime, ModDetector, OverlayGuard, PracticeDetector, RunProgress,
 };
+use process::Target;
+use save_slots::SaveSlot;
+use second_instance::SecondInstance;
+use settings::{PauseMenuPause, Settings, StartTiming};
+
+#[cfg(not(any(feature = "air", feature = "genesis")))]
+compile_error!("At least one target game needs to be enabled through the crate features");
 
 asr::panic_handler!();
-asr::async_main!(nightly);
+asr::async_main!(stable);
 
 async fn main() {
     let mut settings = Settings::register();
+    let mut timer = TimerDriver::new();
+    let mut history = TransitionHistory::new();
+    let mut dump_history = false;
+    let mut self_check = false;
+    let mut heartbeat = Heartbeat::new();
+    let mut read_failures = ReadFailures::new();
+
+    // If the splitter got reloaded mid-run, pick up where the previous instance left off
+    let mut progress = match timer.state() {
+        TimerState::Running | TimerState::Paused => RunProgress::load(),
+        _ => RunProgress::default(),
+    };
+
+    // Whatever belongs to the run is kept outside of the target's scope, so the run carries on when
+    // switching to another target mid-run
+    let mut anomaly = AnomalyDetector::default();
+    let mut act_times = ActTimes::default();
+
+    // Auto start gets disarmed once it fired, when only allowed once, until the runner resets the
+    // timer themselves. Resets from the splitter happen within a tick, so any reset observed
+    // between two ticks is a manual one.
+    let mut start_armed = true;
+    let mut last_timer_state = timer.state();
 
     loop {
-        // Hook to the target process
-        let process = retry(|| PROCESS_NAMES.into_iter().find_map(Process::attach)).await;
+        // Hook to the target process. With two instances running, the one driving the timer is the
+        // configured one, and the other one is only followed.
+        settings.refresh();
+        let (instance, other_instance) = if settings.dual_instances {
+            settings.timer_instance.instances()
+        } else {
+            (0, 1)
+        };
+        let target = Target::attach(instance).await;
+        debug::log!("Attached to {}", target.name());
+        asr::timer::set_variable("Target", target.name());
 
-        process
+        target
             .until_closes(async {
-                // Once the target has been found and attached to, set up default watchers
-                let mut watchers = Watchers::default();
-
-                let wram_base = retry(|| {
-                    process
-                        .memory_ranges()
-                        .find(|x| x.size().unwrap_or_default() == 0x521000)?
-                        .address()
-                        .ok()
-                })
-                .await
-                    + 0x400020;
+                // Once the target has been found and attached to, take a first snapshot of the game
+                let mut current = GameState::read(&target, &GameState::default());
+                let mut game_time = GameTime::new(&current);
+                let mut practice = PracticeDetector::default();
+                let mut second_instance = SecondInstance::new();
+                let mut overlay = OverlayGuard::default();
+                let mut mods = ModDetector::default();
+                let mut pending_start = None;
 
                 loop {
                     // Splitting logic. Adapted from OG LiveSplit:
@@ -50,431 +93,188 @@ async fn main() {
                     // 2. If the timer is currently either running or paused, then the isLoading, gameTime, and reset actions will be run.
                     // 3. If reset does not return true, then the split action will be run.
                     // 4. If the timer is currently not running (and not paused), then the start action will be run.
-                    settings.update();
-                    update_loop(&mut watchers, &process, wram_base);
-
-                    let timer_state = timer::state();
-                    if timer_state == TimerState::Running || timer_state == TimerState::Paused {
-                        if reset(&watchers, &settings) {
-                            timer::reset()
-                        } else if split(&watchers, &settings) {
-                            timer::split()
-                        }
+                    settings.refresh();
+                    let old = current;
+                    target.update();
+                    let memory = target.snapshot();
+                    current = GameState::read(&memory, &old);
+
+                    // Practice tools turn the splitter into an info-only one, same as the dry run
+                    practice.update(&old, &current);
+                    timer.set_dry_run(
+                        settings.dry_run || (settings.suppress_practice && practice.active()),
+                    );
+
+                    if settings.debug_logging {
+                        debug::log_transitions(&old, &current);
                     }
 
-                    if timer::state() == TimerState::NotRunning && start(&watchers, &settings) {
-                        timer::start();
+                    heartbeat.tick();
+                    let failed_reads = target.take_failed_reads();
+                    read_failures.tick(failed_reads);
+                    history.record(&old, &current, settings.debug_logging);
+                    if settings.dump_history && !dump_history {
+                        history.dump();
                     }
+                    dump_history = settings.dump_history;
+                    if settings.self_check && !self_check {
+                        debug::self_check(&current, failed_reads);
+                    }
+                    self_check = settings.self_check;
 
-                    next_tick().await;
-                }
-            })
-            .await;
-    }
-}
-
-#[derive(Default)]
-struct Watchers {
-    levelid: Watcher<Levels>,
-    state: Watcher<u8>,
-    end_of_level_flag: Watcher<bool>,
-    game_ending_flag: Watcher<bool>,
-    time_bonus: Watcher<u16>,
-    save_select: Watcher<u8>,
-    zone_select: Watcher<u8>,
-    save_slot: Watcher<u8>,
-}
-
-#[derive(Gui)]
-struct Settings {
-    #[default = true]
-    /// START: Auto start (No save)
-    start_nosave: bool,
-    #[default = true]
-    /// START: Auto start (Clean save)
-    start_clean_save: bool,
-    #[default = true]
-    /// START: Auto start (Angel Island Zone - No clean save)
-    start_no_clean_save: bool,
-    #[default = true]
-    /// START: Auto start (New Game+)
-    start_new_game_plus: bool,
-    #[default = true]
-    /// RESET: Auto reset
-    reset: bool,
-    #[default = true]
-    /// Angel Island Zone - Act 1
-    angel_island_1: bool,
-    #[default = true]
-    /// Angel Island Zone - Act 2
-    angel_island_2: bool,
-    #[default = true]
-    /// Hydrocity Zone - Act 1
-    hydrocity_1: bool,
-    #[default = true]
-    /// Hydrocity Zone - Act 2
-    hydrocity_2: bool,
-    #[default = true]
-    /// Marble Garden Zone - Act 1
-    marble_garden_1: bool,
-    #[default = true]
-    /// Marble Garden Zone - Act 2
-    marble_garden_2: bool,
-    #[default = true]
-    /// Carnival Night Zone - Act 1
-    carnival_night_1: bool,
-    #[default = true]
-    /// Carnival Night Zone - Act 2
-    carnival_night_2: bool,
-    #[default = true]
-    /// Ice Cap Zone - Act 1
-    ice_cap_1: bool,
-    #[default = true]
-    /// Ice Cap Zone - Act 2
-    ice_cap_2: bool,
-    #[default = true]
-    /// Launch Base Zone - Act 1
-    launch_base_1: bool,
-    #[default = true]
-    /// Launch Base Zone - Act 2
-    launch_base_2: bool,
-    #[default = true]
-    /// Mushroom Hill Zone - Act 1
-    mushroom_hill_1: bool,
-    #[default = true]
-    /// Mushroom Hill Zone - Act 2
-    mushroom_hill_2: bool,
-    #[default = true]
-    /// Flying Battery Zone - Act 1
-    flying_battery_1: bool,
-    #[default = true]
-    /// Flying Battery Zone - Act 2
-    flying_battery_2: bool,
-    #[default = true]
-    /// Sandopolis Zone - Act 1
-    sandopolis_1: bool,
-    #[default = true]
-    /// Sandopolis Zone - Act 2
-    sandopolis_2: bool,
-    #[default = true]
-    /// Lava Reef Zone - Act 1
-    lava_reef_1: bool,
-    #[default = true]
-    /// Lava Reef Zone - Act 2
-    lava_reef_2: bool,
-    #[default = true]
-    /// Hidden Palace Zone
-    hidden_palace: bool,
-    #[default = true]
-    /// Sky Sanctuary Zone
-    sky_sanctuary: bool,
-    #[default = true]
-    /// Death Egg Zone - Act 1
-    death_egg_1: bool,
-    #[default = true]
-    /// Death Egg Zone - Act 2
-    death_egg_2: bool,
-    #[default = true]
-    /// Doomsday Zone
-    doomsday: bool,
-}
-
-fn update_loop(watchers: &mut Watchers, process: &Process, wram_base: Address) {
-    // Filtered state variables. They essentially exclude State.InGame
-    // Used in order to fix a couple of bugs that will otherwise appear with the start trigger
-    let mut state = match &watchers.state.pair {
-        Some(x) => x.current,
-        _ => 0,
-    };
-    let mut save_slot = match &watchers.save_slot.pair {
-        Some(x) => x.current,
-        _ => 0,
-    };
-    let save_select = process
-        .read::<u8>(wram_base + 0xEF4B)
-        .ok()
-        .unwrap_or_default();
-    let cstate = process
-        .read::<u8>(wram_base + 0xF600)
-        .ok()
-        .unwrap_or_default();
-
-    if cstate != STATE_INGAME {
-        state = cstate;
+                    if timer.state() == TimerState::NotRunning
+                        && last_timer_state != TimerState::NotRunning
+                    {
+                        start_armed = true;
+                    }
 
-        if save_select > 0 && save_select <= 8 {
-            save_slot = process
-                .read::<u8>(wram_base + 0xE6AC + 0xA * (save_select as u64 - 1))
-                .ok()
-                .unwrap_or_default();
-        }
-    }
+                    let previous_progress = progress;
+                    let game_time_paused =
+                        game_time.is_paused(&old, &current, &settings, &progress);
+                    mods.update(&current);
+                    let decisions_held = (overlay.update(&current, game_time.is_frozen())
+                        && settings.hold_during_overlay)
+                        || mods.holds(&current, &settings);
+
+                    let timer_state = timer.state();
+                    if is_active(timer_state) || is_active(timer.real_state()) {
+                        progress.update(&old, &current);
+                        progress.count_frames(&old, &current, game_time_paused);
+                        anomaly.update(&old, &current, &progress);
+                        act_times.update(&old, &current);
+                    }
 
-    let mut zone_select = match &watchers.zone_select.pair {
-        Some(x) => x.current,
-        _ => 0,
-    };
+                    if is_active(timer_state) {
+                        timer.set_timer_paused(
+                            settings.pause_menu == PauseMenuPause::Timer && current.paused,
+                        );
+                        match game_time.in_game_time(&current, &settings, &progress) {
+                            Some(time) => timer.set_game_time(time),
+                            None => timer.set_game_time_paused(game_time_paused),
+                        }
 
-    if save_select > 0 && save_select <= 8 {
-        zone_select = process
-            .read::<u8>(wram_base + 0xB15F + 0x4A * (save_select as u64 - 1))
-            .ok()
-            .unwrap_or_default();
-    }
+                        if decisions_held {
+                            // Neither reset nor split until the game's state can be trusted again
+                        } else if let Some(reason) =
+                            logic::reset(&old, &current, &settings, &progress)
+                        {
+                            if settings.debug_logging {
+                                debug::log_decision("reset", reason, &current);
+                            }
+                            timer.reset()
+                        } else if let Some(trigger) =
+                            logic::split(&old, &current, &settings, &progress)
+                        {
+                            if settings.debug_logging {
+                                debug::log_decision("split", trigger, &current);
+                            }
+                            progress.record_split(trigger, &old, &current);
+                            act_times.record_split(trigger, &current);
+                            // Backdated to when the trigger happened, game time getting its actual
+                            // value back on the next tick
+                            if !game_time_paused {
+                                if let Some(time) = game_time.split_time(&settings, &progress) {
+                                    timer.set_game_time(time);
+                                }
+                            }
+                            timer.split()
+                        }
+                    }
 
-    // Define current Act
-    // As act = 0 can both mean Angel Island Act 1 and main menu, we need to check if the LevelStarted flag is set.
-    // If it's not, keep the old value (old.act) in order to allow splitting after returning to the main menu.
-    let mut act = match &watchers.levelid.pair {
-        Some(x) => x.current,
-        _ => Levels::AngelIslandAct1,
-    };
+                    if timer.state() == TimerState::Ended {
+                        act_times.publish_once();
+
+                        // The finished run is cleared for the next one as it starts, which then
+                        // gets started below like after any other reset
+                        if settings.marathon_mode && !decisions_held {
+                            if let Some(reason) = logic::start(&old, &current, &settings) {
+                                if settings.debug_logging {
+                                    debug::log_decision("marathon reset", reason, &current);
+                                }
+                                timer.reset();
+                                start_armed = true;
+                            }
+                        }
+                    }
 
-    let temp_act = process
-        .read::<u8>(wram_base + 0xEE4F)
-        .ok()
-        .unwrap_or_default();
-    let temp_zone = process
-        .read::<u8>(wram_base + 0xEE4E)
-        .ok()
-        .unwrap_or_default();
+                    if timer.state() == TimerState::NotRunning {
+                        // Whatever was tracked belonged to a run that's over. A dry run started
+                        // mid-run only simulates a stopped timer, and the actual run goes on.
+                        let run_over = timer.real_state() == TimerState::NotRunning;
+                        if run_over {
+                            progress = RunProgress::default();
+                            anomaly.clear();
+                            act_times.clear();
+                        }
 
-    act = match temp_act + temp_zone * 10 {
-        0 => {
-            if process
-                .read::<u8>(wram_base + 0xF711)
-                .ok()
-                .unwrap_or_default()
-                != 0
-            {
-                Levels::AngelIslandAct1
-            } else {
-                act
-            }
-        }
-        1 => Levels::AngelIslandAct2,
-        10 => Levels::HydrocityAct1,
-        11 => Levels::HydrocityAct2,
-        20 => Levels::MarbleGardenAct1,
-        21 => Levels::MarbleGardenAct2,
-        30 => Levels::CarnivalNightAct1,
-        31 => Levels::CarnivalNightAct2,
-        50 => Levels::IceCapAct1,
-        51 => Levels::IceCapAct2,
-        60 => Levels::LaunchBaseAct1,
-        61 => Levels::LaunchBaseAct2,
-        70 => Levels::MushroomHillAct1,
-        71 => Levels::MushroomHillAct2,
-        40 => Levels::FlyingBatteryAct1,
-        41 => Levels::FlyingBatteryAct2,
-        80 => Levels::SandopolisAct1,
-        81 => Levels::SandopolisAct2,
-        90 => Levels::LavaReefAct1,
-        91 | 220 => Levels::LavaReefAct2,
-        221 => Levels::HiddenPalace,
-        100 | 101 => Levels::SkySanctuary,
-        110 => Levels::DeathEggAct1,
-        111 | 230 => Levels::DeathEggAct2,
-        120 => Levels::DoomsDay,
-        131 => Levels::Ending,
-        _ => act,
-    };
+                        let armed = start_armed || !settings.start_only_once;
+                        if let Some(reason) = logic::start(&old, &current, &settings)
+                            .filter(|_| !decisions_held && armed)
+                        {
+                            // The run is recorded as the game starts, while the save it's started
+                            // from is still known
+                            let mut started = RunProgress::default();
+                            started.record_start(&old, &current);
+                            pending_start = Some((reason, started));
+                        }
 
-    // Update the watchers
-    watchers.levelid.update_infallible(act);
-    watchers.state.update_infallible(state);
-    watchers.end_of_level_flag.update_infallible(
-        process
-            .read::<u8>(wram_base + 0xFAA8)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    );
-    watchers.game_ending_flag.update_infallible(
-        process
-            .read::<u8>(wram_base + 0xEF72)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    );
-    watchers.time_bonus.update_infallible(
-        process
-            .read::<u16>(wram_base + 0xF7D2)
-            .ok()
-            .unwrap_or_default()
-            .from_be(),
-    );
-    watchers.save_select.update_infallible(save_select);
-    watchers.zone_select.update_infallible(zone_select);
-    watchers.save_slot.update_infallible(save_slot);
-}
+                        // Timing from gaining control holds the start until the player can
+                        // move, unless the game goes back to the menus in the meantime
+                        match pending_start {
+                            Some(_) if logic::in_menu(&current) => pending_start = None,
+                            Some((reason, started))
+                                if settings.start_timing == StartTiming::Load
+                                    || logic::has_control(&current) =>
+                            {
+                                if settings.debug_logging {
+                                    debug::log_decision("start", reason, &current);
+                                }
+                                if run_over {
+                                    progress = started;
+                                }
+                                timer.start();
+                                start_armed = false;
+                                pending_start = None;
+                            }
+                            _ => {}
+                        }
+                    } else {
+                        pending_start = None;
+                    }
 
-fn start(watchers: &Watchers, settings: &Settings) -> bool {
-    let Some(state) = &watchers.state.pair else {
-        return false;
-    };
+                    last_timer_state = timer.state();
 
-    if state.old == STATE_SAVESELECT && state.current == STATE_LOADING {
-        let Some(save_select) = &watchers.save_select.pair else {
-            return false;
-        };
+                    if progress.needs_store(&previous_progress) {
+                        progress.store();
+                    }
 
-        if save_select.current == 0 {
-            return settings.start_nosave;
-        } else {
-            let Some(zone_select) = &watchers.zone_select.pair else {
-                return false;
-            };
+                    current.publish();
+                    if settings.dual_instances {
+                        current.publish_player(instance + 1);
+                        second_instance.tick(other_instance);
+                    }
+                    if current.state == STATE_SAVESELECT {
+                        SaveSlot::publish_all(&memory);
+                    }
+                    anomaly.publish();
+                    mods.publish();
+                    progress.publish(&current, &settings, game_time.frame_rate());
 
-            if zone_select.current == 0 {
-                let Some(save_slot) = &watchers.save_select.pair else {
-                    return false;
-                };
-                if save_slot.old == SAVESLOTSTATE_INPROGRESS {
-                    return settings.start_no_clean_save;
-                } else if save_slot.old == SAVESLOTSTATE_NEWGAME {
-                    return settings.start_clean_save;
-                } else if settings.start_new_game_plus {
-                    return true;
+                    next_tick().await;
                 }
-            }
-        }
-    }
-    false
-}
-
-fn split(watchers: &Watchers, settings: &Settings) -> bool {
-    let Some(act) = &watchers.levelid.pair else {
-        return false;
-    };
-    let Some(game_ending_flag) = &watchers.game_ending_flag.pair else {
-        return false;
-    };
-
-    // If current act is AIZ1 (or an invalid stage) there's no need to continue
-    if act.current == Levels::AngelIslandAct1 {
-        return false;
-    }
-    // If current act is 21 (Sky Sanctuary) and the ending flag becomes true, trigger Knuckles' ending
-    else if settings.sky_sanctuary
-        && act.current == Levels::SkySanctuary
-        && game_ending_flag.current
-        && !game_ending_flag.old
-    {
-        return true;
-    }
-
-    // Special Trigger for Death Egg Zone Act 2 in Act 1: in this case a split needs to be triggered when the Time Bonus drops to zero, in accordance to speedrun.com rulings
-    let Some(time_bonus) = &watchers.time_bonus.pair else {
-        return false;
-    };
-    let Some(end_level_flag) = &watchers.end_of_level_flag.pair else {
-        return false;
-    };
-    if settings.death_egg_2
-        && act.old == Levels::DeathEggAct2
-        && time_bonus.old != 0
-        && time_bonus.current == 0
-        && end_level_flag.current
-    {
-        return true;
-    }
-
-    // Normal splitting condition: trigger a split whenever the act changes
-    act.old != act.current
-        && match act.old {
-            Levels::AngelIslandAct1 => settings.angel_island_1 && end_level_flag.old,
-            Levels::AngelIslandAct2 => settings.angel_island_2,
-            Levels::HydrocityAct1 => settings.hydrocity_1,
-            Levels::HydrocityAct2 => settings.hydrocity_2,
-            Levels::MarbleGardenAct1 => settings.marble_garden_1,
-            Levels::MarbleGardenAct2 => settings.marble_garden_2,
-            Levels::CarnivalNightAct1 => settings.carnival_night_1,
-            Levels::CarnivalNightAct2 => settings.carnival_night_2,
-            Levels::IceCapAct1 => settings.ice_cap_1,
-            Levels::IceCapAct2 => settings.ice_cap_2,
-            Levels::LaunchBaseAct1 => settings.launch_base_1,
-            Levels::LaunchBaseAct2 => settings.launch_base_2,
-            Levels::MushroomHillAct1 => settings.mushroom_hill_1,
-            Levels::MushroomHillAct2 => settings.mushroom_hill_2,
-            Levels::FlyingBatteryAct1 => settings.flying_battery_1,
-            Levels::FlyingBatteryAct2 => settings.flying_battery_2,
-            Levels::SandopolisAct1 => settings.sandopolis_1,
-            Levels::SandopolisAct2 => settings.sandopolis_2,
-            Levels::LavaReefAct1 => settings.lava_reef_1,
-            Levels::LavaReefAct2 => settings.lava_reef_2,
-            Levels::HiddenPalace => settings.hidden_palace,
-            Levels::SkySanctuary => settings.sky_sanctuary,
-            Levels::DeathEggAct1 => settings.death_egg_1,
-            Levels::DeathEggAct2 => settings.death_egg_2,
-            Levels::DoomsDay => settings.doomsday,
-            _ => false,
-        }
-}
+            })
+            .await;
 
-fn reset(watchers: &Watchers, settings: &Settings) -> bool {
-    let Some(save_select) = &watchers.save_select.pair else {
-        return false;
-    };
+        debug::log!("{} closed", target.name());
 
-    if save_select.current == 0 {
-        let Some(state) = &watchers.state.pair else {
-            return false;
-        };
-        if state.old == STATE_SAVESELECT && state.current == STATE_LOADING {
-            return settings.reset;
-        }
-    } else if save_select.current > 0 && save_select.current <= 8 && !save_select.changed() {
-        let Some(save_slot) = &watchers.save_slot.pair else {
-            return false;
-        };
-        if save_slot.old != SAVESLOTSTATE_NEWGAME && save_slot.current == SAVESLOTSTATE_NEWGAME {
-            return settings.reset;
+        // Game time resumes along with the next update on reattach
+        if settings.pause_when_closed && timer.state() == TimerState::Running {
+            timer.set_game_time_paused(true);
         }
+        asr::timer::set_variable("Target", "-");
     }
-    false
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-enum Levels {
-    AngelIslandAct1,
-    AngelIslandAct2,
-    HydrocityAct1,
-    HydrocityAct2,
-    MarbleGardenAct1,
-    MarbleGardenAct2,
-    CarnivalNightAct1,
-    CarnivalNightAct2,
-    IceCapAct1,
-    IceCapAct2,
-    LaunchBaseAct1,
-    LaunchBaseAct2,
-    MushroomHillAct1,
-    MushroomHillAct2,
-    FlyingBatteryAct1,
-    FlyingBatteryAct2,
-    SandopolisAct1,
-    SandopolisAct2,
-    LavaReefAct1,
-    LavaReefAct2,
-    HiddenPalace,
-    SkySanctuary,
-    DeathEggAct1,
-    DeathEggAct2,
-    DoomsDay,
-    Ending,
+const fn is_active(state: TimerState) -> bool {
+    matches!(state, TimerState::Running | TimerState::Paused)
 }
-
-// Consts used in the script
-const STATE_SAVESELECT: u8 = 0x4C;
-const STATE_LOADING: u8 = 0x8C;
-const STATE_INGAME: u8 = 0x0C;
-//const STATE_SPECIALSTAGE: u8 = 0x34;
-//const STATE_EXITINGSPECIALSTAGE: u8 = 0x48;
-const SAVESLOTSTATE_NEWGAME: u8 = 0x80;
-const SAVESLOTSTATE_INPROGRESS: u8 = 0x00;
-//const SAVESLOTSTATE_COMPLETE: u8 = 0x01;
-//const SAVESLOTSTATE_COMPLETEWITHEMERALDS: u8 = 0x02;
-//const SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS: u8 = 0x03;
-
-const PROCESS_NAMES: [&str; 1] = ["Sonic3AIR.exe"];
@@ -0,0 +1,83 @@
+use super::SplitTrigger;
+use crate::{
+    debug::set_variable,
+    game_state::{GameState, Levels, TIMER_FRAME_RATE},
+};
+use arrayvec::ArrayString;
+use asr::timer;
+use core::fmt::Write;
+
+/// Final in-game time of every act played during the run, for an IL-style breakdown once the run is over
+#[derive(Default)]
+pub struct ActTimes {
+    frames: [Option<u32>; Levels::ACTS.len()],
+    published: bool,
+}
+
+impl ActTimes {
+    pub fn update(&mut self, old: &GameState, current: &GameState) {
+        // The act timer stops at the tally, so the last value read in the act is its final time
+        if old.level != current.level {
+            self.record(old.level, old.level_timer);
+        }
+    }
+
+    /// The splits ending the run fire in the act itself, the DEZ2 time bonus or Knuckles' ending in
+    /// Sky Sanctuary, and the timer stops before any level change could record it
+    pub fn record_split(&mut self, trigger: SplitTrigger, current: &GameState) {
+        if trigger == SplitTrigger::Act(current.level) {
+            self.record(current.level, current.level_timer);
+        }
+    }
+
+    fn record(&mut self, act: Levels, frames: u32) {
+        if frames == 0 {
+            return;
+        }
+        if let Some(index) = Levels::ACTS.iter().position(|&level| level == act) {
+            self.frames[index] = Some(frames);
+        }
+    }
+
+    /// Forgets the run, blanking the times published for it so they don't linger into the next one
+    pub fn clear(&mut self) {
+        if self.published {
+            for (level, frames) in Levels::ACTS.into_iter().zip(self.frames) {
+                if frames.is_some() {
+                    timer::set_variable(&variable(level), "-");
+                }
+            }
+        }
+        *self = Self::default();
+    }
+
+    /// Publishes the time of every act that got played, once per run
+    pub fn publish_once(&mut self) {
+        if self.published {
+            return;
+        }
+        self.published = true;
+
+        for (level, frames) in Levels::ACTS.into_iter().zip(self.frames) {
+            let Some(frames) = frames else {
+                continue;
+            };
+
+            let seconds = frames / TIMER_FRAME_RATE;
+            set_variable!(
+                &variable(level),
+                "{}:{:02}.{:02}",
+                seconds / 60,
+                seconds % 60,
+                frames % TIMER_FRAME_RATE * 100 / TIMER_FRAME_RATE
+            );
+        }
+    }
+}
+
+/// Name of the variable holding the act's time
+fn variable(act: Levels) -> ArrayString<64> {
+    let mut key = ArrayString::new();
+    let _ = write!(key, "IGT - {}", act.name());
+    key
+}
@@ -0,0 +1,136 @@
+use super::RunProgress;
+use crate::{
+    debug::{log, set_variable},
+    game_state::{GameState, Levels},
+};
+use asr::timer;
+
+/// Largest amount of rings the game can award at once. Super ring monitors give 10 and the slot
+/// machine bonus stage pays out in bursts, but never anywhere near a hundred.
+const MAX_RING_GAIN: u16 = 100;
+
+/// Value changes the game itself can't produce, hinting that the memory was edited during the run
+#[derive(Clone, Copy, Debug)]
+pub enum Anomaly {
+    RingsJump,
+    LivesJump,
+    LevelTeleport,
+}
+
+/// Watches for impossible value changes and latches the first one seen during the run, as an
+/// automatic red flag for moderators
+#[derive(Default)]
+pub struct AnomalyDetector {
+    detected: Option<Anomaly>,
+}
+
+impl AnomalyDetector {
+    pub fn update(&mut self, old: &GameState, current: &GameState, progress: &RunProgress) {
+        if self.detected.is_some() {
+            return;
+        }
+
+        let anomaly = if current.rings > old.rings.saturating_add(MAX_RING_GAIN) {
+            Some(Anomaly::RingsJump)
+        } else if current.lives > old.lives.saturating_add(1) && !lives_refilled(old, current) {
+            // Every source of extra lives gives them one at a time
+            Some(Anomaly::LivesJump)
+        } else if old.level != current.level
+            && old.state == current.state
+            && progress.restarting().is_none()
+            && !old.screen_faded_out
+            && !current.screen_faded_out
+            && !known_transition(current, old.level, current.level)
+        {
+            // Loading a save or going through the menus changes the state, anything else has to
+            // follow the character's route. The level ID can read as garbage while an act reloads
+            // or the screen is black, which isn't any level change.
+            Some(Anomaly::LevelTeleport)
+        } else {
+            None
+        };
+
+        if let Some(anomaly) = anomaly {
+            log!("Anomaly detected: {:?}", anomaly);
+            self.detected = Some(anomaly);
+        }
+    }
+
+    /// Clears the flag, for a new run
+    pub fn clear(&mut self) {
+        self.detected = None;
+    }
+
+    pub fn publish(&self) {
+        match self.detected {
+            Some(anomaly) => set_variable!("Anomaly detected", "{:?}", anomaly),
+            None => timer::set_variable("Anomaly detected", "No"),
+        }
+    }
+}
+
+/// Whether the game can go straight from one level to the other, on the route of the character
+/// being played
+fn known_transition(current: &GameState, from: Levels, to: Levels) -> bool {
+    current.character.next_act(from, current.emeralds) == Some(to)
+        || matches!(
+            (from, to),
+            // Knuckles' story ends in Sky Sanctuary, everyone else's in Doomsday, or right after
+            // Death Egg without all the emeralds
+            (
+                Levels::SkySanctuary | Levels::DeathEggAct2 | Levels::DoomsDay,
+                Levels::Ending
+            )
+        )
+}
+
+/// Whether the game hands out a fresh set of lives, which it does on a continue and whenever it
+/// switches game mode, like loading a save
+fn lives_refilled(old: &GameState, current: &GameState) -> bool {
+    old.lives == 0 || old.game_mode != current.game_mode
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Character, STATE_INGAME, STATE_LOADING};
+
+    fn in_level(character: Character, level: Levels) -> GameState {
+        let mut state = GameState::default();
+        state.character = character;
+        state.level = level;
+        state.state = STATE_LOADING;
+        state.game_mode = STATE_INGAME;
+        state.lives = 3;
+        state
+    }
+
+    fn detected(character: Character, from: Levels, to: Levels) -> bool {
+        let mut detector = AnomalyDetector::default();
+        let old = in_level(character, from);
+        let current = in_level(character, to);
+        detector.update(&old, &current, &RunProgress::default());
+        detector.detected.is_some()
+    }
+
+    #[test]
+    fn knuckles_goes_from_mushroom_hill_to_sandopolis() {
+        let (from, to) = (Levels::MushroomHillAct2, Levels::SandopolisAct1);
+        assert!(!detected(Character::Knuckles, from, to));
+        assert!(detected(Character::Sonic, from, to));
+        assert!(!detected(Character::Sonic, from, Levels::FlyingBatteryAct1));
+    }
+
+    #[test]
+    fn no_teleport_while_an_act_reloads() {
+        let mut detector = AnomalyDetector::default();
+        let old = in_level(Character::Sonic, Levels::IceCapAct1);
+        let current = in_level(Character::Sonic, Levels::DeathEggAct1);
+        let progress = RunProgress {
+            restarting: Some(Levels::IceCapAct1),
+            ..Default::default()
+        };
+        detector.update(&old, &current, &progress);
+        assert!(detector.detected.is_none());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::game_state::{GameState, Levels};
+
+/// Every boss of the game, mini-bosses included, in the order they're fought in a full game run.
+/// Launch Base Zone Act 2 holds two of them: the act's own boss, then Big Arm after the Death Egg launch.
+pub const BOSSES: [Levels; 26] = [
+    Levels::AngelIslandAct1,
+    Levels::AngelIslandAct2,
+    Levels::HydrocityAct1,
+    Levels::HydrocityAct2,
+    Levels::MarbleGardenAct1,
+    Levels::MarbleGardenAct2,
+    Levels::CarnivalNightAct1,
+    Levels::CarnivalNightAct2,
+    Levels::IceCapAct1,
+    Levels::IceCapAct2,
+    Levels::LaunchBaseAct1,
+    Levels::LaunchBaseAct2,
+    Levels::LaunchBaseAct2,
+    Levels::MushroomHillAct1,
+    Levels::MushroomHillAct2,
+    Levels::FlyingBatteryAct1,
+    Levels::FlyingBatteryAct2,
+    Levels::SandopolisAct1,
+    Levels::SandopolisAct2,
+    Levels::LavaReefAct1,
+    Levels::LavaReefAct2,
+    Levels::HiddenPalace,
+    Levels::SkySanctuary,
+    Levels::DeathEggAct1,
+    Levels::DeathEggAct2,
+    Levels::DoomsDay,
+];
+
+// The boss flag is also cleared when the player dies during the fight and the act restarts, so a
+// fight only counts as won if no life was lost since it started
+pub fn boss_defeated(old: &GameState, current: &GameState, lives_at_fight_start: u8) -> bool {
+    old.boss_flag && !current.boss_flag && current.lives >= lives_at_fight_start
+}
@@ -0,0 +1,26 @@
+use super::SplitTrigger;
+use crate::game_state::{
+    CompetitionStage, GameState, STATE_COMPETITIONMENU, STATE_COMPETITIONRESULTS, STATE_INGAME,
+    STATE_LOADING,
+};
+
+// A race starts with one of the competition stages loading, whether it's the first race of a
+// Grand Prix or a single Match Race
+pub fn start(old: &GameState, current: &GameState) -> bool {
+    old.game_mode != STATE_LOADING
+        && current.game_mode == STATE_LOADING
+        && CompetitionStage::from_zone(current.zone).is_some()
+}
+
+// Every race ends on the results screen, once both players are done or time ran out
+pub fn split(old: &GameState, current: &GameState) -> Option<SplitTrigger> {
+    let stage = CompetitionStage::from_zone(old.zone)?;
+    (old.game_mode == STATE_INGAME && current.game_mode == STATE_COMPETITIONRESULTS)
+        .then_some(SplitTrigger::Race(stage))
+}
+
+// Grand Prix races follow each other through the results screen, so the menu is only ever seen
+// again once the players are done
+pub fn reset(old: &GameState, current: &GameState) -> bool {
+    old.game_mode != STATE_COMPETITIONMENU && current.game_mode == STATE_COMPETITIONMENU
+}
@@ -0,0 +1,227 @@
+use super::{
+    frames_between, in_bonus_stage, in_special_stage, is_loading, time_bonus_pending, RunProgress,
+    MAX_FRAMES_PER_TICK,
+};
+use crate::{
+    debug::{log, SecondWindow, NOMINAL_TICK_RATE},
+    game_state::{timer_frames_to_duration, GameState, STATE_SAVESELECT},
+    settings::{GameTimeSource, PauseMenuPause, Settings},
+};
+use asr::{time::Duration, timer};
+
+/// Number of consecutive ticks without the frame counter advancing before the game is considered
+/// frozen. The runtime ticks faster than the game runs, so a single tick without a new frame is normal.
+const FROZEN_TICKS: u32 = 15;
+
+/// How many frames worth of ticks without a new frame it takes to consider the game frozen, when
+/// it's simulated slowly enough for that to be longer than `FROZEN_TICKS`
+const FROZEN_FRAMES: u32 = 3;
+
+/// Number of ticks the game needs to stay frozen to be considered hung, rather than paused or
+/// briefly stalled by the host
+const HUNG_TICKS: u32 = 10 * NOMINAL_TICK_RATE;
+
+/// Decides whether game time should currently be paused, and keeps track of the rate the game
+/// is simulated at, so everything derived from frames can be scaled accordingly
+pub struct GameTime {
+    stalled_ticks: u32,
+    window: SecondWindow,
+    window_frames: u32,
+    window_frozen: bool,
+    frame_rate: u32,
+    hung: bool,
+}
+
+impl GameTime {
+    /// Starts off assuming the game runs at the rate of the console it runs as, until measured
+    pub const fn new(current: &GameState) -> Self {
+        Self {
+            stalled_ticks: 0,
+            window: SecondWindow::new(),
+            window_frames: 0,
+            window_frozen: false,
+            frame_rate: current.nominal_frame_rate(),
+            hung: false,
+        }
+    }
+}
+
+impl GameTime {
+    /// Frames the game simulates per second, as measured over the last second it wasn't frozen
+    pub const fn frame_rate(&self) -> u32 {
+        self.frame_rate
+    }
+
+    /// Whether the game stopped simulating. A slowed down simulation naturally goes several ticks
+    /// without a new frame, which isn't mistaken for the game being frozen.
+    pub fn is_frozen(&self) -> bool {
+        let ticks_per_frame = NOMINAL_TICK_RATE.div_ceil(self.frame_rate.max(1));
+        self.stalled_ticks >= FROZEN_TICKS.max(FROZEN_FRAMES * ticks_per_frame)
+    }
+
+    /// Keeps track of the game hanging, reporting whenever it hangs or recovers
+    fn update_hung(&mut self) {
+        let hung = self.stalled_ticks >= HUNG_TICKS;
+        if hung != self.hung {
+            self.hung = hung;
+            if hung {
+                log!("The game appears to be hung");
+            } else {
+                log!("The game recovered");
+            }
+            timer::set_variable("Game hung", if hung { "Yes" } else { "No" });
+        }
+    }
+
+    /// Measures the simulation rate over windows of a second of wall clock time. Windows where
+    /// the game froze at any point are discarded, as they'd only measure the stall.
+    fn measure_rate(&mut self, old: &GameState, current: &GameState) {
+        self.window_frames = self
+            .window_frames
+            .saturating_add(frames_between(old, current));
+        self.window_frozen |= self.is_frozen();
+
+        if let Some(milliseconds) = self.window.close() {
+            if self.window_frames != 0 && !self.window_frozen {
+                self.frame_rate = self.window_frames * 1000 / milliseconds;
+                timer::set_variable_int("Simulation rate (fps)", self.frame_rate);
+            }
+            self.window_frames = 0;
+            self.window_frozen = false;
+        }
+    }
+
+    /// Game time as measured by the game itself, when the chosen source is one of the in-game
+    /// timers rather than real time
+    pub fn in_game_time(
+        &self,
+        current: &GameState,
+        settings: &Settings,
+        progress: &RunProgress,
+    ) -> Option<Duration> {
+        match settings.game_time_source {
+            GameTimeSource::RealTime => None,
+            GameTimeSource::ActIgt => Some(current.level_time()),
+            GameTimeSource::CumulativeIgt => {
+                Some(timer_frames_to_duration(progress.cumulative_igt(current)))
+            }
+            // Frames are only counted while game time isn't paused, which is how every pause,
+            // including the pause menu's, applies to them
+            GameTimeSource::Frames => Some(self.frames_to_duration(progress.elapsed_frames)),
+        }
+    }
+
+    /// Time the given frames took at the measured simulation rate, so a game simulated faster or
+    /// slower than the console through A.I.R.'s options or mods still times what was played
+    fn frames_to_duration(&self, frames: u32) -> Duration {
+        Duration::nanoseconds(frames as i64 * 1_000_000_000 / self.frame_rate.max(1) as i64)
+    }
+
+    /// Game time to record the split that was just taken with, when it differs from the one the
+    /// timer already has. Counting frames, the ones the game ran past the trigger before a late
+    /// tick detected it are taken back off. The in-game timers and real time can't tell when the
+    /// trigger happened, and keep the time they read.
+    pub fn split_time(&self, settings: &Settings, progress: &RunProgress) -> Option<Duration> {
+        match settings.game_time_source {
+            GameTimeSource::Frames => {
+                // Only the frames counted on this tick can be taken back
+                let late_frames = progress.last_split_delay().min(MAX_FRAMES_PER_TICK - 1);
+                Some(self.frames_to_duration(progress.elapsed_frames.saturating_sub(late_frames)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_paused(
+        &mut self,
+        old: &GameState,
+        current: &GameState,
+        settings: &Settings,
+        progress: &RunProgress,
+    ) -> bool {
+        // The game stops simulating when the process is suspended by the OS, when A.I.R. pauses
+        // itself after losing focus, when an emulator is paused or when the game hits a breakpoint
+        if current.frame_counter == old.frame_counter {
+            self.stalled_ticks = self.stalled_ticks.saturating_add(1);
+        } else {
+            self.stalled_ticks = 0;
+        }
+        self.measure_rate(old, current);
+        self.update_hung();
+
+        let suspended = settings.pause_when_suspended && self.is_frozen();
+
+        // Keeps runs with and without the intro skip comparable, by only counting game time from
+        // the moment the player gains control
+        let intro = settings.exclude_intro && progress.in_intro(current);
+
+        // Segmented runs exit to the data select screen between sessions. The state only leaves
+        // the save select value once a file is loaded again.
+        let in_menu = settings.menu_return_pauses && current.state == STATE_SAVESELECT;
+
+        // Race and relay formats stop the clock between segments, once the run is under way
+        let between_segments = settings.pause_on_data_select
+            && progress.splits != 0
+            && current.game_mode == STATE_SAVESELECT;
+
+        let hung = settings.pause_when_hung && self.hung;
+
+        let pause_menu = settings.pause_menu == PauseMenuPause::GameTime && current.paused;
+
+        let loading = settings.remove_loads && is_loading(current);
+
+        let special_stage = settings.pause_in_special_stages && in_special_stage(current);
+
+        let bonus_stage = settings.pause_in_bonus_stages && in_bonus_stage(current);
+
+        // RTA-TB leaves the time bonus countdown out. The Death Egg Zone Act 2 split happens as the
+        // bonus runs out, which is also when game time resumes.
+        let time_bonus = settings.pause_during_time_bonus && time_bonus_pending(current);
+
+        suspended
+            || hung
+            || loading
+            || special_stage
+            || bonus_stage
+            || time_bonus
+            || pause_menu
+            || intro
+            || in_menu
+            || between_segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asr::settings::Gui;
+
+    #[test]
+    fn pause_menu_stops_frame_derived_game_time() {
+        let mut settings = Settings::register();
+        settings.game_time_source = GameTimeSource::Frames;
+        settings.pause_menu = PauseMenuPause::GameTime;
+
+        let mut old = GameState::default();
+        old.frame_counter = 100;
+        let mut game_time = GameTime::new(&old);
+        let mut progress = RunProgress::default();
+
+        let mut tick = |old: &mut GameState, paused: bool| {
+            let mut current = *old;
+            current.frame_counter += 1;
+            current.paused = paused;
+            let game_time_paused = game_time.is_paused(old, &current, &settings, &progress);
+            progress.count_frames(old, &current, game_time_paused);
+            *old = current;
+            game_time.in_game_time(&current, &settings, &progress)
+        };
+
+        let running = tick(&mut old, false);
+        assert_ne!(running, Some(Duration::ZERO));
+        for _ in 0..60 {
+            assert_eq!(tick(&mut old, true), running);
+        }
+        assert!(tick(&mut old, false) > running);
+    }
+}
@@ -0,0 +1,954 @@
+mod act_times;
+mod anomaly;
+mod bosses;
+mod competition;
+mod game_time;
+mod mods;
+mod overlay;
+mod practice;
+#[cfg(test)]
+mod replay;
+mod reset;
+mod split;
+mod start;
+mod time_attack;
+
+pub use act_times::ActTimes;
+pub use anomaly::AnomalyDetector;
+pub use game_time::GameTime;
+pub use mods::ModDetector;
+pub use overlay::OverlayGuard;
+pub use practice::PracticeDetector;
+pub use reset::reset;
+pub use split::split;
+pub use start::start;
+
+use crate::debug::{log, set_variable};
+use crate::{
+    game_state::{
+        Character, CompetitionStage, Form, GameState, Levels, SAVESLOTSTATE_COMPLETE,
+        SAVESLOTSTATE_COMPLETEWITHEMERALDS, SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS,
+        STATE_CONTINUE, STATE_DEMO, STATE_EXITINGSPECIALSTAGE, STATE_INGAME, STATE_LEVELSELECT,
+        STATE_LOADING, STATE_SAVESELECT, STATE_SPECIALSTAGE, STATE_TITLE,
+    },
+    save_slots::is_save_slot,
+    settings::Settings,
+};
+use asr::{settings, timer};
+use bosses::BOSSES;
+use core::ops::RangeInclusive;
+
+/// What caused a split to be triggered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitTrigger {
+    /// Completion of the given act
+    Act(Levels),
+    SpecialStages,
+    PerfectBonus,
+    /// First Super transformation of the run
+    Super,
+    /// First Hyper transformation of the run
+    Hyper,
+    /// Death Egg launch cutscene in Launch Base Zone Act 2
+    DeathEggLaunch,
+    /// Defeat of a boss or mini-boss in the given act
+    Boss(Levels),
+    /// Seventh Chaos Emerald collected
+    AllChaosEmeralds,
+    /// Super Emerald collected
+    SuperEmerald,
+    /// Big Arm defeated, at the end of Launch Base Zone Act 2
+    BigArm,
+    /// Race completed in the 2P Competition mode
+    Race(CompetitionStage),
+}
+
+impl SplitTrigger {
+    /// Bit in the fired events mask, for triggers that can only fire once per run
+    const fn event_bit(self) -> u32 {
+        match self {
+            Self::Super => 1 << 0,
+            Self::Hyper => 1 << 1,
+            Self::DeathEggLaunch => 1 << 2,
+            Self::AllChaosEmeralds => 1 << 3,
+            Self::BigArm => 1 << 4,
+            _ => 0,
+        }
+    }
+}
+
+// Keys used to persist the run progress in the settings map
+const KEY_FIRED_SPLITS: &str = "_run_fired_splits";
+const KEY_FIRED_EVENTS: &str = "_run_fired_events";
+const KEY_FIRED_BOSSES: &str = "_run_fired_bosses";
+const KEY_SPECIAL_STAGES: &str = "_run_special_stages_completed";
+const KEY_GIANT_RINGS: &str = "_run_giant_rings_entered";
+const KEY_BONUS_STAGES: &str = "_run_bonus_stages_entered";
+const KEY_PERFECT_BONUSES: &str = "_run_perfect_bonuses";
+const KEY_BOSSES_DEFEATED: &str = "_run_bosses_defeated";
+const KEY_RESTARTS: &str = "_run_restarts";
+const KEY_DEATHS: &str = "_run_deaths";
+const KEY_ELAPSED_FRAMES: &str = "_run_elapsed_frames";
+const KEY_NG_PLUS: &str = "_run_ng_plus";
+
+/// Most frames counted between two ticks. Anything above comes from the host hitching, the frame
+/// counter starting over or being read before the game set it up, and only counts up to this.
+const MAX_FRAMES_PER_TICK: u32 = 60;
+
+/// Frames counted between two writes of the progress to the settings map, when nothing but the
+/// frame counts changed
+const STORE_INTERVAL_FRAMES: u32 = 60;
+const KEY_CHARACTER: &str = "_run_character";
+const KEY_SAVE_SLOT: &str = "_run_save_slot";
+const KEY_MENU_FRAMES: &str = "_run_menu_frames";
+const KEY_SPLITS: &str = "_run_splits";
+const KEY_COMPLETED_ACTS_TIME: &str = "_run_completed_acts_time";
+const KEY_ACT_TIME_LATCHED: &str = "_run_act_time_latched";
+const KEY_ACT_RESTARTS: &str = "_run_act_restarts";
+const KEY_ALL_CHAOS_EMERALDS: &str = "_run_all_chaos_emeralds";
+const KEY_BOSS_FIGHT_LIVES: &str = "_run_boss_fight_lives";
+const KEY_DOOMSDAY_BOSS_DEFEATED: &str = "_run_doomsday_boss_defeated";
+const KEY_LAUNCH_BASE_2_BOSSES: &str = "_run_launch_base_2_bosses";
+const KEY_BOSS_OUT_OF_SEQUENCE: &str = "_run_boss_out_of_sequence";
+const KEY_LAST_SPLIT_DELAY: &str = "_run_last_split_delay";
+const KEY_START_FRAME: &str = "_run_start_frame";
+const KEY_INTRO_FRAMES: &str = "_run_intro_frames";
+const KEY_RESTARTING: &str = "_run_restarting";
+const KEY_LIFE_LOST: &str = "_run_life_lost";
+
+/// Counters accumulated over the course of a single run. They get cleared whenever a new run is started.
+///
+/// The progress is persisted in the settings map, so reloading the splitter mid-run doesn't
+/// cause acts that already split to split again.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct RunProgress {
+    pub special_stages_completed: u32,
+    pub giant_rings_entered: u32,
+    pub bonus_stages_entered: u32,
+    pub perfect_bonuses: u32,
+    /// Whether the last Chaos Emerald got collected during the run
+    pub all_chaos_emeralds: bool,
+    pub bosses_defeated: u32,
+    pub restarts: u32,
+    pub act_restarts: u32,
+    pub deaths: u32,
+    /// Character the run was started with
+    pub character: Character,
+    /// Save slot the run was started on, 0 when playing without a save
+    pub save_slot: u8,
+    /// Whether the run was started from a cleared save, keeping its emeralds
+    pub ng_plus: bool,
+    pub menu_frames: u32,
+    /// Splits triggered so far, of any kind
+    pub splits: u32,
+    /// Sum of the in-game times of the acts left so far, in frames of the act timer
+    pub completed_acts_time: u32,
+    act_time_latched: bool,
+    /// Frames simulated while game time was running, for game time counted in frames
+    pub elapsed_frames: u32,
+    boss_fight_lives: u8,
+    doomsday_boss_defeated: bool,
+    launch_base_2_bosses: u8,
+    boss_out_of_sequence: bool,
+    fired_splits: u32,
+    fired_events: u32,
+    fired_bosses: u32,
+    last_split_delay: u32,
+    pub start_frame: u32,
+    intro_frames: Option<u32>,
+    restarting: Option<Levels>,
+    life_lost: bool,
+}
+
+impl RunProgress {
+    /// Restores the progress stored in the settings map
+    pub fn load() -> Self {
+        let map = settings::Map::load();
+        // Values that may be missing are stored as -1
+        let get_i64 = |key| map.get(key).and_then(|value| value.get_i64());
+        let get = |key| get_i64(key).unwrap_or_default() as u32;
+        let get_option = |key| {
+            get_i64(key)
+                .filter(|&value| value >= 0)
+                .map(|value| value as u32)
+        };
+
+        Self {
+            special_stages_completed: get(KEY_SPECIAL_STAGES),
+            giant_rings_entered: get(KEY_GIANT_RINGS),
+            bonus_stages_entered: get(KEY_BONUS_STAGES),
+            perfect_bonuses: get(KEY_PERFECT_BONUSES),
+            all_chaos_emeralds: get(KEY_ALL_CHAOS_EMERALDS) != 0,
+            bosses_defeated: get(KEY_BOSSES_DEFEATED),
+            restarts: get(KEY_RESTARTS),
+            act_restarts: get(KEY_ACT_RESTARTS),
+            deaths: get(KEY_DEATHS),
+            character: Character::from_player_mode(get(KEY_CHARACTER) as u16),
+            save_slot: get(KEY_SAVE_SLOT) as u8,
+            ng_plus: get(KEY_NG_PLUS) != 0,
+            menu_frames: get(KEY_MENU_FRAMES),
+            splits: get(KEY_SPLITS),
+            completed_acts_time: get(KEY_COMPLETED_ACTS_TIME),
+            act_time_latched: get(KEY_ACT_TIME_LATCHED) != 0,
+            elapsed_frames: get(KEY_ELAPSED_FRAMES),
+            boss_fight_lives: get(KEY_BOSS_FIGHT_LIVES) as u8,
+            doomsday_boss_defeated: get(KEY_DOOMSDAY_BOSS_DEFEATED) != 0,
+            launch_base_2_bosses: get(KEY_LAUNCH_BASE_2_BOSSES) as u8,
+            boss_out_of_sequence: get(KEY_BOSS_OUT_OF_SEQUENCE) != 0,
+            fired_splits: get(KEY_FIRED_SPLITS),
+            fired_events: get(KEY_FIRED_EVENTS),
+            fired_bosses: get(KEY_FIRED_BOSSES),
+            last_split_delay: get(KEY_LAST_SPLIT_DELAY),
+            start_frame: get(KEY_START_FRAME),
+            intro_frames: get_option(KEY_INTRO_FRAMES),
+            restarting: get_option(KEY_RESTARTING).and_then(|value| {
+                Levels::ACTS
+                    .into_iter()
+                    .find(|&level| level as u32 == value)
+            }),
+            life_lost: get(KEY_LIFE_LOST) != 0,
+        }
+    }
+
+    /// Whether the progress changed enough since the given one to be written to the settings map.
+    /// The frame counts go up on every tick, and alone only get written every so often.
+    pub fn needs_store(&self, previous: &Self) -> bool {
+        let frames_aside = Self {
+            elapsed_frames: previous.elapsed_frames,
+            menu_frames: previous.menu_frames,
+            ..*self
+        };
+        frames_aside != *previous
+            || self.elapsed_frames / STORE_INTERVAL_FRAMES
+                != previous.elapsed_frames / STORE_INTERVAL_FRAMES
+            || self.menu_frames / STORE_INTERVAL_FRAMES
+                != previous.menu_frames / STORE_INTERVAL_FRAMES
+    }
+
+    /// Writes the progress to the settings map. The map is shared with the settings the user
+    /// edits, so the write is retried on top of whatever changed in the meantime.
+    pub fn store(&self) {
+        loop {
+            let old = settings::Map::load();
+            let map = old.clone();
+            self.insert_into(&map);
+            if map.store_if_unchanged(&old) {
+                break;
+            }
+        }
+    }
+
+    fn insert_into(&self, map: &settings::Map) {
+        let insert = |key, value: i64| map.insert(key, &value.into());
+        insert(KEY_FIRED_SPLITS, self.fired_splits as i64);
+        insert(KEY_FIRED_EVENTS, self.fired_events as i64);
+        insert(KEY_FIRED_BOSSES, self.fired_bosses as i64);
+        insert(KEY_SPECIAL_STAGES, self.special_stages_completed as i64);
+        insert(KEY_GIANT_RINGS, self.giant_rings_entered as i64);
+        insert(KEY_BONUS_STAGES, self.bonus_stages_entered as i64);
+        insert(KEY_PERFECT_BONUSES, self.perfect_bonuses as i64);
+        insert(KEY_ALL_CHAOS_EMERALDS, self.all_chaos_emeralds as i64);
+        insert(KEY_BOSSES_DEFEATED, self.bosses_defeated as i64);
+        insert(KEY_RESTARTS, self.restarts as i64);
+        insert(KEY_ACT_RESTARTS, self.act_restarts as i64);
+        insert(KEY_DEATHS, self.deaths as i64);
+        insert(KEY_CHARACTER, self.character as i64);
+        insert(KEY_SAVE_SLOT, self.save_slot as i64);
+        insert(KEY_NG_PLUS, self.ng_plus as i64);
+        insert(KEY_MENU_FRAMES, self.menu_frames as i64);
+        insert(KEY_SPLITS, self.splits as i64);
+        insert(KEY_COMPLETED_ACTS_TIME, self.completed_acts_time as i64);
+        insert(KEY_ACT_TIME_LATCHED, self.act_time_latched as i64);
+        insert(KEY_ELAPSED_FRAMES, self.elapsed_frames as i64);
+        insert(KEY_BOSS_FIGHT_LIVES, self.boss_fight_lives as i64);
+        insert(
+            KEY_DOOMSDAY_BOSS_DEFEATED,
+            self.doomsday_boss_defeated as i64,
+        );
+        insert(KEY_LAUNCH_BASE_2_BOSSES, self.launch_base_2_bosses as i64);
+        insert(KEY_BOSS_OUT_OF_SEQUENCE, self.boss_out_of_sequence as i64);
+        insert(KEY_LAST_SPLIT_DELAY, self.last_split_delay as i64);
+        insert(KEY_START_FRAME, self.start_frame as i64);
+        insert(
+            KEY_INTRO_FRAMES,
+            self.intro_frames.map_or(-1, |frames| frames as i64),
+        );
+        insert(
+            KEY_RESTARTING,
+            self.restarting.map_or(-1, |level| level as i64),
+        );
+        insert(KEY_LIFE_LOST, self.life_lost as i64);
+    }
+
+    /// Marks the beginning of a new run
+    pub fn record_start(&mut self, old: &GameState, current: &GameState) {
+        self.start_frame = current.frame_counter;
+        self.character = current.character;
+        self.save_slot = current.save_select;
+        // The slot's state is the one it had on the data select screen, before the game loads it
+        self.ng_plus = is_save_slot(current.save_select)
+            && matches!(
+                old.save_slot,
+                SAVESLOTSTATE_COMPLETE
+                    | SAVESLOTSTATE_COMPLETEWITHEMERALDS
+                    | SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS
+            );
+    }
+
+    /// Counts the frames the game went through since the last tick, unless game time is paused.
+    /// Tick timing doesn't matter here, every frame gets counted once whenever it's seen.
+    pub fn count_frames(&mut self, old: &GameState, current: &GameState, paused: bool) {
+        if !paused {
+            self.elapsed_frames = self
+                .elapsed_frames
+                .saturating_add(frames_between(old, current));
+        }
+    }
+
+    pub fn update(&mut self, old: &GameState, current: &GameState) {
+        if self.intro_frames.is_none() && intro_finished(old, current) {
+            self.intro_frames = Some(current.frame_counter.wrapping_sub(self.start_frame));
+        }
+
+        if act_restart_began(old, current) {
+            self.restarting = Some(old.level);
+        } else if current.game_mode == STATE_INGAME {
+            // The bonus stage can get its zone only once loaded, after the reload began
+            if in_bonus_stage(current) {
+                self.restarting = None;
+            }
+            // Only a reload landing back on the same act is a restart
+            if self.restarting.take() == Some(current.level) {
+                self.act_restarts += 1;
+                self.restarts += 1;
+            }
+            if self.restarting.is_none() && old.game_mode != STATE_INGAME {
+                self.life_lost = false;
+            }
+        }
+
+        if old.level != current.level && self.restarting.is_none() {
+            self.act_restarts = 0;
+        }
+
+        // The act's time is final as soon as it's cleared, the timer only stopping for the tally
+        // afterwards. Acts left without a tally count up to the last time read in them. Deaths
+        // don't count as leaving the act, so the time lost to them is simply gone.
+        if !old.end_of_level_flag && current.end_of_level_flag && !self.act_time_latched {
+            self.completed_acts_time += current.level_timer;
+            self.act_time_latched = true;
+        } else if old.level != current.level && self.restarting.is_none() {
+            if !self.act_time_latched && old.game_mode == STATE_INGAME {
+                self.completed_acts_time += old.level_timer;
+            }
+            self.act_time_latched = false;
+        }
+
+        if life_lost(old, current) {
+            self.deaths += 1;
+            self.life_lost = true;
+        }
+
+        if in_menu(current) {
+            self.menu_frames = self
+                .menu_frames
+                .saturating_add(frames_between(old, current));
+        }
+
+        if special_stage_completed(old, current) {
+            self.special_stages_completed += 1;
+        }
+
+        if all_chaos_emeralds_collected(old, current) {
+            self.all_chaos_emeralds = true;
+        }
+
+        if giant_ring_entered(old, current) {
+            self.giant_rings_entered += 1;
+        }
+
+        if !in_bonus_stage(old) && in_bonus_stage(current) {
+            self.bonus_stages_entered += 1;
+        }
+
+        if perfect_bonus_awarded(old, current) {
+            self.perfect_bonuses += 1;
+        }
+
+        if !old.boss_flag && current.boss_flag {
+            self.boss_fight_lives = current.lives;
+        } else if self.boss_defeated(old, current) {
+            // Checked against the expected sequence, so a missed or misread boss gets noticed
+            // instead of silently shifting every following split
+            if self.next_boss() != Some(current.level) && !self.boss_out_of_sequence {
+                log!("Boss defeated out of sequence in {:?}", current.level);
+                self.boss_out_of_sequence = true;
+            }
+            self.bosses_defeated += 1;
+            if current.level == Levels::LaunchBaseAct2 {
+                self.launch_base_2_bosses += 1;
+            }
+            if current.level == Levels::DoomsDay {
+                self.doomsday_boss_defeated = true;
+            }
+        }
+    }
+
+    pub fn record_split(&mut self, trigger: SplitTrigger, old: &GameState, current: &GameState) {
+        // The trigger happened somewhere between the two snapshots. Any frame the game ran past
+        // the first one is a frame the split may have been detected late by, which happens when
+        // the host hitches and ticks get delayed.
+        self.last_split_delay = current
+            .frame_counter
+            .wrapping_sub(old.frame_counter)
+            .saturating_sub(1);
+
+        self.splits += 1;
+        match trigger {
+            SplitTrigger::Act(level) => self.fired_splits |= 1 << level as u32,
+            SplitTrigger::Boss(level) => self.fired_bosses |= 1 << level as u32,
+            _ => {}
+        }
+        self.fired_events |= trigger.event_bit();
+    }
+
+    /// In-game time of the whole run so far, in frames of the act timer: every act left so far,
+    /// plus the current one until it's cleared
+    pub const fn cumulative_igt(&self, current: &GameState) -> u32 {
+        if self.act_time_latched {
+            self.completed_acts_time
+        } else {
+            self.completed_acts_time + current.level_timer
+        }
+    }
+
+    /// Whether the run is still waiting for the player to gain control in Angel Island
+    pub fn in_intro(&self, current: &GameState) -> bool {
+        self.intro_frames.is_none()
+            && current.level == Levels::AngelIslandAct1
+            && current.control_locked
+    }
+
+    pub fn boss_defeated(&self, old: &GameState, current: &GameState) -> bool {
+        bosses::boss_defeated(old, current, self.boss_fight_lives)
+    }
+
+    /// The Death Egg launches as soon as the act's own boss is defeated, the first one fought in
+    /// Launch Base Zone Act 2. Big Arm only comes afterwards, once the launch is over.
+    pub fn death_egg_launched(&self, old: &GameState, current: &GameState) -> bool {
+        current.level == Levels::LaunchBaseAct2
+            && self.boss_defeated(old, current)
+            && self.launch_base_2_bosses == 1
+    }
+
+    /// Big Arm is the second boss fought in Launch Base Zone Act 2, after the act's own boss
+    pub fn big_arm_defeated(&self, old: &GameState, current: &GameState) -> bool {
+        current.level == Levels::LaunchBaseAct2
+            && self.boss_defeated(old, current)
+            && self.launch_base_2_bosses >= 2
+    }
+
+    /// Whether the final boss got its last hit, which is when Doomsday is over
+    pub const fn doomsday_boss_defeated(&self) -> bool {
+        self.doomsday_boss_defeated
+    }
+
+    /// The next boss expected in the All Bosses sequence
+    pub fn next_boss(&self) -> Option<Levels> {
+        BOSSES.get(self.bosses_defeated as usize).copied()
+    }
+
+    /// Whether the act reload beginning on this tick was asked for through A.I.R.'s restart act or
+    /// checkpoint options, rather than following a death
+    pub fn act_restart_requested(&self, old: &GameState, current: &GameState) -> bool {
+        act_restart_began(old, current) && !self.life_lost
+    }
+
+    /// Frames the last split was detected late by, as the game ran past its trigger
+    pub const fn last_split_delay(&self) -> u32 {
+        self.last_split_delay
+    }
+
+    /// The act being reloaded, from the moment a restart begins until the game is back in the act
+    pub const fn restarting(&self) -> Option<Levels> {
+        self.restarting
+    }
+
+    pub fn has_split(&self, level: Levels) -> bool {
+        self.fired_splits & 1 << level as u32 != 0
+    }
+
+    /// Whether an act further in the game than the given one already produced a split
+    pub fn has_split_past(&self, level: Levels) -> bool {
+        self.fired_splits >> (level as u32 + 1) != 0
+    }
+
+    /// Whether a boss of the given act already produced a split during the current run
+    pub fn has_split_on_boss(&self, level: Levels) -> bool {
+        self.fired_bosses & 1 << level as u32 != 0
+    }
+
+    /// Whether a trigger that can only fire once per run already did
+    pub fn has_fired(&self, trigger: SplitTrigger) -> bool {
+        self.fired_events & trigger.event_bit() != 0
+    }
+
+    /// The first act of the character's route, starting from the current one, that is set to split
+    /// and hasn't split yet
+    pub fn next_split(&self, current: &GameState, settings: &Settings) -> Option<Levels> {
+        self.character
+            .route(current.emeralds)
+            .iter()
+            .copied()
+            .skip_while(|&level| level != current.level)
+            .find(|&level| {
+                settings.act_split_enabled_for(level, self.character, current.emeralds)
+                    && !self.has_split(level)
+            })
+    }
+
+    /// Acts split so far, out of the ones of the character's route set to split. Splits outside of
+    /// those, like the ending, aren't counted.
+    fn run_progress(&self, current: &GameState, settings: &Settings) -> (u32, u32) {
+        let route = self
+            .character
+            .route(current.emeralds)
+            .iter()
+            .filter(|&&level| {
+                settings.act_split_enabled_for(level, self.character, current.emeralds)
+            })
+            .fold(0u32, |mask, &level| mask | 1 << level as u32);
+        ((self.fired_splits & route).count_ones(), route.count_ones())
+    }
+
+    pub fn publish(&self, current: &GameState, settings: &Settings, frame_rate: u32) {
+        timer::set_variable(
+            "Next split",
+            self.next_split(current, settings).map_or("-", Levels::name),
+        );
+        let (done, total) = self.run_progress(current, settings);
+        set_variable!("Run progress", "{}/{}", done, total);
+        timer::set_variable("New Game+", if self.ng_plus { "Yes" } else { "No" });
+        timer::set_variable(
+            "Route end",
+            self.character.final_act(current.emeralds).name(),
+        );
+        timer::set_variable_int("Last split delay (frames)", self.last_split_delay);
+        timer::set_variable_int(
+            "Last split delay (ms)",
+            self.last_split_delay * 1000 / frame_rate.max(1),
+        );
+        match self.intro_frames {
+            Some(frames) => {
+                timer::set_variable_int("Intro length (frames)", frames);
+                timer::set_variable(
+                    "Intro skipped",
+                    if frames < INTRO_SKIPPED_SECONDS * current.nominal_frame_rate() {
+                        "Yes"
+                    } else {
+                        "No"
+                    },
+                );
+            }
+            None => {
+                timer::set_variable("Intro length (frames)", "-");
+                timer::set_variable("Intro skipped", "-");
+            }
+        }
+        timer::set_variable_int("Giant rings entered", self.giant_rings_entered);
+        timer::set_variable_int("Bonus stages entered", self.bonus_stages_entered);
+        timer::set_variable(
+            "In special stage",
+            if in_special_stage(current) {
+                "Yes"
+            } else {
+                "No"
+            },
+        );
+        timer::set_variable_int("Special stages completed", self.special_stages_completed);
+        // A stage still being played has yet to be won or lost
+        timer::set_variable_int(
+            "Special stages failed",
+            self.giant_rings_entered
+                .saturating_sub(self.special_stages_completed + in_special_stage(current) as u32),
+        );
+        timer::set_variable_int("Chaos Emeralds", current.chaos_emeralds);
+        timer::set_variable_int("Super Emeralds", current.super_emeralds);
+        timer::set_variable_int("Perfect bonuses", self.perfect_bonuses);
+        let menu_centiseconds = self.menu_frames as u64 * 100 / frame_rate.max(1) as u64;
+        set_variable!(
+            "Menu time",
+            "{}:{:02}.{:02}",
+            menu_centiseconds / 6000,
+            menu_centiseconds / 100 % 60,
+            menu_centiseconds % 100
+        );
+        timer::set_variable_int("Act restarts", self.act_restarts);
+        timer::set_variable_int("Run restarts", self.restarts);
+        timer::set_variable_int("Deaths", self.deaths);
+        if settings.all_bosses {
+            set_variable!(
+                "Bosses defeated",
+                "{}/{}{}",
+                self.bosses_defeated,
+                BOSSES.len(),
+                if self.boss_out_of_sequence {
+                    " (out of sequence)"
+                } else {
+                    ""
+                }
+            );
+            timer::set_variable("Next boss", self.next_boss().map_or("-", Levels::name));
+        }
+    }
+}
+
+// Frames the game went through between two ticks, up to what a single tick can account for
+fn frames_between(old: &GameState, current: &GameState) -> u32 {
+    current
+        .frame_counter
+        .wrapping_sub(old.frame_counter)
+        .min(MAX_FRAMES_PER_TICK)
+}
+
+/// Seconds between the start trigger and the player gaining control in Angel Island, below which
+/// the opening cutscene is considered to have been skipped through A.I.R.'s option. The full
+/// cutscene alone takes well over this.
+const INTRO_SKIPPED_SECONDS: u32 = 10;
+
+/// Zones of the bonus stages reached through star posts: Gumball Machine, Glowing Spheres and Slot
+/// Machine
+const BONUS_STAGE_ZONES: RangeInclusive<u8> = 0x13..=0x15;
+
+// The opening of Angel Island ends the first time the player gets control in the act. The option
+// to skip the cutscene isn't stored in the emulated RAM, so this is how its effect gets measured.
+pub fn intro_finished(old: &GameState, current: &GameState) -> bool {
+    current.level == Levels::AngelIslandAct1
+        && current.game_mode == STATE_INGAME
+        && old.control_locked
+        && !current.control_locked
+}
+
+// A level reload that doesn't follow the act being cleared is either a death or A.I.R.'s restart
+// act feature. The few story transitions without a tally reload too, but land on a different act.
+pub fn act_restart_began(old: &GameState, current: &GameState) -> bool {
+    old.game_mode == STATE_INGAME
+        && current.game_mode == STATE_LOADING
+        && !old.end_of_level_flag
+        && !old.game_ending_flag
+        // Star posts send the player to the bonus stages and back through a reload as well
+        && !BONUS_STAGE_ZONES.contains(&old.zone)
+        && !BONUS_STAGE_ZONES.contains(&current.zone)
+}
+
+// Lives only ever go down by one, when the player dies. Going through the menus or a continue sets
+// the counter anew, which isn't a death even when it ends up lower.
+pub fn life_lost(old: &GameState, current: &GameState) -> bool {
+    old.game_mode == STATE_INGAME
+        && current.game_mode == STATE_INGAME
+        && old.level == current.level
+        && current.lives.wrapping_add(1) == old.lives
+}
+
+// The attract demos run real levels, only under their own game mode. Like the level's, it has the
+// top bit set while the demo loads.
+pub fn in_demo(current: &GameState) -> bool {
+    current.game_mode & 0x7F == STATE_DEMO
+}
+
+// The player has control once in the act with the controls unlocked, which the intro and the
+// title cards keep locked
+pub fn has_control(current: &GameState) -> bool {
+    current.game_mode == STATE_INGAME && !current.control_locked
+}
+
+// The menus keep the game running, so the time spent in them is measured in frames as well
+pub fn in_menu(current: &GameState) -> bool {
+    matches!(
+        current.game_mode,
+        STATE_TITLE | STATE_CONTINUE | STATE_LEVELSELECT | STATE_SAVESELECT
+    )
+}
+
+// Loads are the level loading state and the black screens around it: the fade out of the data
+// select screen, and the blackout between two acts once the previous one has been cleared
+pub fn is_loading(current: &GameState) -> bool {
+    match current.game_mode {
+        STATE_LOADING => true,
+        STATE_SAVESELECT => current.screen_faded_out,
+        STATE_INGAME => current.end_of_level_flag && current.screen_faded_out,
+        _ => false,
+    }
+}
+
+/// Frames since boot within which a rewind of the frame counter is attributed to the game resetting
+const BOOT_FRAMES: u32 = 600;
+
+// Resetting the console (or reloading the ROM) reinitializes the whole RAM, bringing the frame
+// counter back close to zero
+pub fn console_reset(old: &GameState, current: &GameState) -> bool {
+    current.frame_counter < old.frame_counter && current.frame_counter < BOOT_FRAMES
+}
+
+// Covers both Super and Hyper forms, as the player turns directly into the latter once every
+// Super Emerald has been collected
+pub fn transformed(old: &GameState, current: &GameState) -> bool {
+    old.form == Form::Normal && current.form != Form::Normal
+}
+
+// Not an edge, as going Hyper also counts as the first Super transformation: when both split on
+// the same tick, the Hyper split follows on the next one
+pub fn is_hyper(current: &GameState) -> bool {
+    current.form == Form::Hyper
+}
+
+// The time bonus is shown at the tally as soon as the act is cleared, and drained into the score.
+// It being left is what makes the tally's length vary with the time the act took.
+pub fn time_bonus_pending(current: &GameState) -> bool {
+    current.end_of_level_flag && current.time_bonus != 0
+}
+
+// The special stage lasts until its results screen is over, whether the emerald was won or not
+pub fn in_special_stage(current: &GameState) -> bool {
+    matches!(
+        current.game_mode,
+        STATE_SPECIALSTAGE | STATE_EXITINGSPECIALSTAGE
+    )
+}
+
+// Bonus stages are played as levels of their own, loaded through the same game mode as any act
+pub fn in_bonus_stage(current: &GameState) -> bool {
+    current.game_mode & 0x7F == STATE_INGAME && BONUS_STAGE_ZONES.contains(&current.zone)
+}
+
+// Giant rings are the only way to reach a special stage during normal gameplay, so entering one is detected
+// by the game switching to the special stage state
+pub fn giant_ring_entered(old: &GameState, current: &GameState) -> bool {
+    old.state != STATE_SPECIALSTAGE && current.state == STATE_SPECIALSTAGE
+}
+
+// The Perfect bonus is only ever set at the act tally, once every ring in the act has been collected
+pub fn perfect_bonus_awarded(old: &GameState, current: &GameState) -> bool {
+    current.end_of_level_flag && old.perfect_bonus == 0 && current.perfect_bonus != 0
+}
+
+// A special stage counts as completed when the emerald count goes up while inside the stage or on its results screen.
+// Failed stages don't award an emerald, so they are ignored here.
+pub fn special_stage_completed(old: &GameState, current: &GameState) -> bool {
+    (current.state == STATE_SPECIALSTAGE || current.state == STATE_EXITINGSPECIALSTAGE)
+        && current.emeralds > old.emeralds
+}
+
+// Only special stages award Chaos Emeralds, the seventh one completing the set
+pub fn all_chaos_emeralds_collected(old: &GameState, current: &GameState) -> bool {
+    special_stage_completed(old, current) && old.chaos_emeralds < 7 && current.chaos_emeralds >= 7
+}
+
+// Super Emeralds replace the Chaos Emeralds once all seven have been collected, and are awarded by
+// the same special stages
+pub fn super_emerald_collected(old: &GameState, current: &GameState) -> bool {
+    special_stage_completed(old, current) && current.super_emeralds > old.super_emeralds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asr::settings::Gui;
+
+    /// Xorshift generator, so the sequences below are the same on every run
+    struct Rng(u32);
+
+    impl Rng {
+        fn next(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn below(&mut self, bound: u32) -> u32 {
+            self.next() % bound
+        }
+
+        fn flag(&mut self) -> bool {
+            self.next() & 1 != 0
+        }
+    }
+
+    /// A random in-game tick, keeping clear of the values the special split rules react to
+    fn random_in_game(rng: &mut Rng, level: Levels, frame_counter: u32) -> GameState {
+        let mut state = GameState::default();
+        state.level = level;
+        state.state = STATE_LOADING;
+        state.game_mode = STATE_INGAME;
+        state.frame_counter = frame_counter;
+        state.end_of_level_flag = rng.flag();
+        state.screen_faded_out = rng.flag();
+        state.control_locked = rng.flag();
+        state.time_bonus = rng.below(3) as u16 * 100;
+        state.rings = rng.below(1000) as u16;
+        state.lives = 1 + rng.below(9) as u8;
+        state.level_timer = rng.below(10 * 60 * 60);
+        state
+    }
+
+    #[test]
+    fn no_split_without_an_act_change() {
+        let settings = Settings::register();
+        let progress = RunProgress::default();
+        let mut rng = Rng(0x5EC5_1234);
+
+        for _ in 0..10_000 {
+            // The DEZ2 tally splits on its own rule, the time bonus running out
+            let level = loop {
+                let level = Levels::ACTS[rng.below(Levels::ACTS.len() as u32) as usize];
+                if level != Levels::DeathEggAct2 {
+                    break level;
+                }
+            };
+            let frame = rng.next() >> 1;
+            let old = random_in_game(&mut rng, level, frame);
+            let current = random_in_game(&mut rng, level, frame + 1);
+
+            assert_eq!(
+                split(&old, &current, &settings, &progress),
+                None,
+                "{old:?} -> {current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_reset_in_game() {
+        let settings = Settings::register();
+        let mut rng = Rng(0xA17_5EED);
+
+        for _ in 0..10_000 {
+            let progress = RunProgress {
+                save_slot: rng.below(9) as u8,
+                start_frame: rng.next() >> 2,
+                ..Default::default()
+            };
+            let level = Levels::ACTS[rng.below(Levels::ACTS.len() as u32) as usize];
+            let frame = rng.next() >> 1;
+            let mut old = random_in_game(&mut rng, level, frame);
+            let mut current = random_in_game(&mut rng, level, frame + 1);
+            // The save slot values only follow the game while it's out of a level
+            old.save_select = rng.below(9) as u8;
+            old.save_slot = rng.below(4) as u8;
+            current.save_select = old.save_select;
+            current.save_slot = old.save_slot;
+
+            assert_eq!(
+                reset(&old, &current, &settings, &progress),
+                None,
+                "{old:?} -> {current:?}"
+            );
+        }
+    }
+
+    fn in_angel_island(control_locked: bool, frame_counter: u32) -> GameState {
+        let mut state = GameState::default();
+        state.level = Levels::AngelIslandAct1;
+        // The filtered state keeps the loading state it had when the act started
+        state.state = STATE_LOADING;
+        state.game_mode = STATE_INGAME;
+        state.control_locked = control_locked;
+        state.frame_counter = frame_counter;
+        state
+    }
+
+    #[test]
+    fn intro_ends_when_control_unlocks_in_angel_island() {
+        let mut progress = RunProgress {
+            start_frame: 100,
+            ..Default::default()
+        };
+        let old = in_angel_island(true, 400);
+        let current = in_angel_island(false, 401);
+
+        assert!(progress.in_intro(&old));
+        progress.update(&old, &current);
+        assert_eq!(progress.intro_frames, Some(301));
+        assert!(!progress.in_intro(&old));
+    }
+
+    #[test]
+    fn bonus_stage_is_not_a_restart() {
+        let mut progress = RunProgress::default();
+        let mut ticks = [in_angel_island(false, 0); 5];
+        // Star post, the bonus stage, and back to the act
+        ticks[1].game_mode = STATE_LOADING;
+        ticks[2].zone = 0x14;
+        ticks[3].zone = 0x14;
+        ticks[3].game_mode = STATE_LOADING;
+
+        for pair in ticks.windows(2) {
+            progress.update(&pair[0], &pair[1]);
+        }
+        assert_eq!(progress.restarting, None);
+        assert_eq!(progress.restarts, 0);
+    }
+
+    #[test]
+    fn next_split_follows_the_route() {
+        let mut settings = Settings::register();
+        let progress = RunProgress {
+            character: Character::Knuckles,
+            fired_splits: 1 << Levels::MushroomHillAct2 as u32,
+            ..Default::default()
+        };
+        let mut current = GameState::default();
+        current.level = Levels::MushroomHillAct2;
+
+        for presets in [true, false] {
+            settings.character_presets = presets;
+            assert_eq!(
+                progress.next_split(&current, &settings),
+                Some(Levels::SandopolisAct1)
+            );
+        }
+    }
+
+    #[test]
+    fn run_progress_counts_the_route_acts_only() {
+        let mut settings = Settings::register();
+        settings.character_presets = true;
+        settings.ending = true;
+        let current = GameState::default();
+        let (_, total) = RunProgress::default().run_progress(&current, &settings);
+
+        // Every act of the route split, along with the ending
+        let sonic = RunProgress {
+            fired_splits: u32::MAX,
+            ..Default::default()
+        };
+        assert_eq!(sonic.run_progress(&current, &settings), (total, total));
+
+        // Knuckles never plays Flying Battery, and can still get through all of his splits
+        let knuckles = RunProgress {
+            character: Character::Knuckles,
+            fired_splits: Character::Knuckles
+                .route(0)
+                .iter()
+                .fold(1 << Levels::Ending as u32, |mask, &level| {
+                    mask | 1 << level as u32
+                }),
+            ..Default::default()
+        };
+        let (done, total) = knuckles.run_progress(&current, &settings);
+        assert_eq!(done, total);
+        assert!(!settings.act_split_enabled_for(Levels::FlyingBatteryAct1, Character::Knuckles, 0));
+    }
+
+    #[test]
+    fn intro_goes_on_while_loading() {
+        let mut progress = RunProgress::default();
+        let old = in_angel_island(true, 10);
+        let mut current = in_angel_island(false, 11);
+        current.game_mode = STATE_LOADING;
+
+        progress.update(&old, &current);
+        assert_eq!(progress.intro_frames, None);
+    }
+}
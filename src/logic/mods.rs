@@ -0,0 +1,67 @@
+use crate::{
+    debug::{log, set_variable},
+    game_state::{GameState, LAST_ZONE, STATE_INGAME},
+    settings::{ModCompatibility, Settings},
+};
+use asr::timer;
+
+/// Last player mode of the unmodded game, Knuckles alone
+const LAST_PLAYER_MODE: u16 = 3;
+
+/// Traces left in the emulated RAM by script mods, values the unmodded game never uses
+#[derive(Clone, Copy, Debug)]
+pub enum ModSign {
+    ExtraCharacter,
+    ExtraLevel,
+}
+
+/// Looks out for mods adding characters or levels, which reuse the RAM the splitter reads in ways
+/// the game itself doesn't. Mods can't be toggled without restarting the game, so whatever is seen
+/// stays detected for as long as the game runs.
+#[derive(Default)]
+pub struct ModDetector {
+    detected: Option<ModSign>,
+}
+
+impl ModDetector {
+    pub fn update(&mut self, current: &GameState) {
+        if self.detected.is_some() || current.game_mode != STATE_INGAME {
+            return;
+        }
+
+        let sign = if current.player_mode > LAST_PLAYER_MODE {
+            Some(ModSign::ExtraCharacter)
+        } else if current.zone > LAST_ZONE {
+            Some(ModSign::ExtraLevel)
+        } else {
+            None
+        };
+
+        if let Some(sign) = sign {
+            log!("Mod detected: {:?}", sign);
+            self.detected = Some(sign);
+        }
+    }
+
+    /// Whether the splitter accounts for mods, either as detected or as told by the runner
+    pub fn active(&self, settings: &Settings) -> bool {
+        match settings.mod_compatibility {
+            ModCompatibility::Auto => self.detected.is_some(),
+            ModCompatibility::Never => false,
+            ModCompatibility::Always => true,
+        }
+    }
+
+    /// Whether timer decisions should be held off on this tick. The level ID of a level added by a
+    /// mod reads as whichever act was played last, so nothing happening in there can be attributed.
+    pub fn holds(&self, current: &GameState, settings: &Settings) -> bool {
+        self.active(settings) && current.game_mode == STATE_INGAME && current.zone > LAST_ZONE
+    }
+
+    pub fn publish(&self) {
+        match self.detected {
+            Some(sign) => set_variable!("Mods detected", "{:?}", sign),
+            None => timer::set_variable("Mods detected", "No"),
+        }
+    }
+}
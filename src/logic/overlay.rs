@@ -0,0 +1,38 @@
+use crate::game_state::GameState;
+
+/// Frames the game needs to run uninterrupted after an overlay closes before its state is trusted again
+const SETTLE_FRAMES: u32 = 10;
+
+/// Holds off timer decisions while A.I.R.'s options or extras overlay is open mid-run.
+///
+/// The overlay freezes the simulation and drives its menus through the same memory the game uses,
+/// which can transiently look like the data select screen or a new game being started.
+#[derive(Default)]
+pub struct OverlayGuard {
+    settle_until: Option<u32>,
+}
+
+impl OverlayGuard {
+    /// Returns whether timer decisions should be held off on this tick
+    pub fn update(&mut self, current: &GameState, frozen: bool) -> bool {
+        if frozen || !is_game_mode(current.game_mode) {
+            self.settle_until = Some(current.frame_counter.wrapping_add(SETTLE_FRAMES));
+            return true;
+        }
+
+        match self.settle_until {
+            Some(frame) if (frame.wrapping_sub(current.frame_counter) as i32) > 0 => true,
+            _ => {
+                self.settle_until = None;
+                false
+            }
+        }
+    }
+}
+
+// Every game mode of the original game is a multiple of 4 up to the time attack, with the high bit
+// set while a level is being loaded. Anything else is A.I.R. using the memory for its own menus.
+const fn is_game_mode(game_mode: u8) -> bool {
+    let mode = game_mode & 0x7F;
+    mode.is_multiple_of(4) && mode <= 0x50
+}
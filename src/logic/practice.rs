@@ -0,0 +1,37 @@
+use super::console_reset;
+use crate::{
+    debug::log,
+    game_state::{GameState, STATE_SAVESELECT},
+};
+
+/// Detects the use of practice tools, which should never drive a real timer.
+///
+/// A.I.R. mods aren't visible from the emulated RAM, so they're recognised by what they do to the
+/// game instead: loading a savestate rewinds the frame counter, and warping around usually goes
+/// through the debug mode.
+#[derive(Default)]
+pub struct PracticeDetector {
+    active: bool,
+}
+
+impl PracticeDetector {
+    pub fn update(&mut self, old: &GameState, current: &GameState) {
+        if !self.active {
+            let savestate_loaded =
+                current.frame_counter < old.frame_counter && !console_reset(old, current);
+
+            if savestate_loaded || current.debug_mode {
+                log!("Practice tools detected, timer actions are suppressed");
+                self.active = true;
+            }
+        } else if current.state == STATE_SAVESELECT && !current.debug_mode {
+            // Going back to the data select screen is where a new, clean attempt would begin
+            log!("Practice tools no longer in use");
+            self.active = false;
+        }
+    }
+
+    pub const fn active(&self) -> bool {
+        self.active
+    }
+}
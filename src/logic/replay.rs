@@ -0,0 +1,104 @@
+//! Replays scripted WRAM recordings through the start, split and reset logic, tick by tick, the same
+//! way the main loop feeds them what it reads from the game.
+//!
+//! A recording is a list of ticks. `tick` (or `tick N` for N identical ticks) begins the next one,
+//! `XXXX: bytes...` writes big endian bytes at a WRAM offset before it's read, and `=> Decision`
+//! expects the timer to start, split or reset during it. Anything after a `#` is a comment.
+//! Memory keeps its contents from one tick to the next, and the frame counter advances by one frame
+//! on every tick.
+
+use super::{reset, split, start, RunProgress};
+use crate::{game_state::GameState, process::Wram, settings::Settings};
+use arrayvec::ArrayString;
+use asr::settings::Gui;
+use core::fmt::Write;
+
+/// Offset of the frame counter in WRAM
+const FRAME_COUNTER: u32 = 0xFE0C;
+
+/// Log of the decisions taken over a recording, one line each, along with the block of ticks
+type Log = ArrayString<1024>;
+
+struct Replay {
+    wram: Wram,
+    old: GameState,
+    progress: RunProgress,
+    running: bool,
+    frames: u32,
+}
+
+impl Replay {
+    fn tick(&mut self, settings: &Settings, block: usize, log: &mut Log) {
+        self.frames += 1;
+        self.wram.write(FRAME_COUNTER, &self.frames.to_be_bytes());
+        let current = GameState::read(&self.wram, &self.old);
+        let old = self.old;
+
+        if self.running {
+            self.progress.update(&old, &current);
+            if let Some(reason) = reset(&old, &current, settings, &self.progress) {
+                self.running = false;
+                writeln!(log, "{block}: Reset({reason:?})").unwrap();
+            } else if let Some(trigger) = split(&old, &current, settings, &self.progress) {
+                self.progress.record_split(trigger, &old, &current);
+                writeln!(log, "{block}: Split({trigger:?})").unwrap();
+            }
+        } else if let Some(reason) = start(&old, &current, settings) {
+            self.running = true;
+            self.progress = RunProgress::default();
+            self.progress.record_start(&old, &current);
+            writeln!(log, "{block}: Start({reason:?})").unwrap();
+        }
+
+        self.old = current;
+    }
+}
+
+/// Plays a recording back, and checks the decisions taken match the ones it expects
+fn replay(recording: &str) {
+    let settings = Settings::register();
+    let mut replay = Replay {
+        wram: Wram::new(),
+        old: GameState::default(),
+        progress: RunProgress::default(),
+        running: false,
+        frames: 0,
+    };
+    let (mut taken, mut expected) = (Log::new(), Log::new());
+    let (mut block, mut repeat) = (0, 0);
+
+    for line in recording.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some(count) = line.strip_prefix("tick") {
+            for _ in 0..repeat {
+                replay.tick(&settings, block, &mut taken);
+            }
+            block += 1;
+            repeat = count.trim().parse().unwrap_or(1);
+        } else if let Some(decision) = line.strip_prefix("=>") {
+            writeln!(expected, "{block}: {}", decision.trim()).unwrap();
+        } else if let Some((offset, bytes)) = line.split_once(':') {
+            let offset = u32::from_str_radix(offset.trim(), 16).unwrap();
+            for (index, byte) in (0..).zip(bytes.split_whitespace()) {
+                replay
+                    .wram
+                    .write(offset + index, &[u8::from_str_radix(byte, 16).unwrap()]);
+            }
+        }
+    }
+    for _ in 0..repeat {
+        replay.tick(&settings, block, &mut taken);
+    }
+
+    assert_eq!(taken, expected);
+}
+
+#[test]
+fn new_game() {
+    replay(include_str!("fixtures/new_game.txt"));
+}
+
+#[test]
+fn death_egg() {
+    replay(include_str!("fixtures/death_egg.txt"));
+}
@@ -0,0 +1,115 @@
+use super::{competition, console_reset, in_bonus_stage, time_attack, RunProgress};
+use crate::{
+    game_state::{
+        GameState, Levels, SAVESLOTSTATE_NEWGAME, STATE_INGAME, STATE_LOADING, STATE_SAVESELECT,
+    },
+    save_slots::is_save_slot,
+    settings::Settings,
+};
+
+/// What caused the timer to be reset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetReason {
+    Competition,
+    TimeAttack,
+    ConsoleReset,
+    AngelIslandRestart,
+    GameOver,
+    NoSaveStart,
+    SaveWipe,
+}
+
+pub fn reset(
+    old: &GameState,
+    current: &GameState,
+    settings: &Settings,
+    progress: &RunProgress,
+) -> Option<ResetReason> {
+    // Back to back runs only ever move on to the next run through its start
+    if settings.marathon_mode && settings.marathon_skip_reset {
+        return None;
+    }
+
+    // Same as the start, competition mode only follows its own menus
+    if current.competition_mode {
+        return (settings.competition_reset && competition::reset(old, current))
+            .then_some(ResetReason::Competition);
+    }
+
+    if settings.time_attack {
+        return (settings.reset && time_attack::reset(old, current))
+            .then_some(ResetReason::TimeAttack);
+    }
+
+    // Save slots work differently on emulators, so the game being restarted is the only reliable trigger there
+    if settings.reset && settings.reset_on_console_reset && console_reset(old, current) {
+        return Some(ResetReason::ConsoleReset);
+    }
+
+    // Restarting the first act is how a run gets retried without going back to the menus. A bonus
+    // stage keeps the act it was entered from, and reloads on the way in and out of it.
+    if settings.reset
+        && settings.reset_on_angel_island_restart
+        && old.level == Levels::AngelIslandAct1
+        && !in_bonus_stage(old)
+        && !in_bonus_stage(current)
+        && progress.act_restart_requested(old, current)
+        && !past_minimum_time(current, settings, progress)
+    {
+        return Some(ResetReason::AngelIslandRestart);
+    }
+
+    // Only a run that hasn't split yet is thrown away, a later Game Over is played through with a
+    // continue or a save reload
+    if settings.reset
+        && settings.reset_on_game_over
+        && progress.splits == 0
+        && game_over(old, current)
+        && !past_minimum_time(current, settings, progress)
+    {
+        return Some(ResetReason::GameOver);
+    }
+
+    if current.save_select == 0 {
+        // Starting again without a save is the same as re-entering the file the run is on, which
+        // only pauses game time when menu returns are treated as a pause
+        if old.state == STATE_SAVESELECT && current.state == STATE_LOADING {
+            // Starting another game without a save is a single button press away from the menu
+            return (settings.reset
+                && settings.reset_on_no_save_start
+                && settings.save_slot_filter.allows(0)
+                && !settings.menu_return_pauses
+                && !past_minimum_time(current, settings, progress))
+            .then_some(ResetReason::NoSaveStart);
+        }
+    } else if is_save_slot(current.save_select)
+        && old.save_select == current.save_select
+        && old.save_slot != SAVESLOTSTATE_NEWGAME
+        && current.save_slot == SAVESLOTSTATE_NEWGAME
+    {
+        // Clearing another slot than the run's is only housekeeping, when told so
+        let same_slot = current.save_select == progress.save_slot;
+        return (settings.reset
+            && settings.reset_on_save_wipe
+            && settings.save_slot_filter.allows(current.save_select)
+            && (same_slot || !settings.reset_same_slot_only))
+            .then_some(ResetReason::SaveWipe);
+    }
+    None
+}
+
+// Past the minimum duration, only deliberate resets are allowed: a cleared save slot or a console reset
+fn past_minimum_time(current: &GameState, settings: &Settings, progress: &RunProgress) -> bool {
+    current.frame_counter.wrapping_sub(progress.start_frame)
+        >= settings
+            .reset_minimum_time
+            .frames(current.nominal_frame_rate())
+}
+
+// Losing the last life in a level starts the Game Over sequence
+fn game_over(old: &GameState, current: &GameState) -> bool {
+    old.game_mode == STATE_INGAME
+        && current.game_mode == STATE_INGAME
+        && old.lives != 0
+        && current.lives == 0
+}
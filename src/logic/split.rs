@@ -0,0 +1,440 @@
+use super::{
+    competition, in_demo, is_hyper, perfect_bonus_awarded, special_stage_completed,
+    super_emerald_collected, time_attack, transformed, RunProgress, SplitTrigger,
+};
+use crate::{
+    game_state::{Character, GameState, Levels},
+    settings::{DoomsdaySplit, Settings},
+};
+
+pub fn split(
+    old: &GameState,
+    current: &GameState,
+    settings: &Settings,
+    progress: &RunProgress,
+) -> Option<SplitTrigger> {
+    // Nothing a demo does is part of the run, even with the timer left running on the title screen
+    if in_demo(old) || in_demo(current) {
+        return None;
+    }
+
+    // Competition mode fills some of the same RAM in its own way, which the triggers below would misread
+    if current.competition_mode {
+        if !settings.competition_split {
+            return None;
+        }
+        return competition::split(old, current);
+    }
+
+    if settings.time_attack {
+        return time_attack::split(old, current);
+    }
+
+    // The All Bosses preset replaces every other trigger
+    if settings.all_bosses {
+        return progress
+            .boss_defeated(old, current)
+            .then_some(SplitTrigger::Boss(current.level));
+    }
+
+    // Sonic 3 alone ends with Big Arm, the last boss before the Sonic & Knuckles half of the game.
+    // Rules timing the Sonic 3 half of a full game run stop there too, rather than on the act change.
+    if (settings.sonic_3_alone || settings.boss_big_arm)
+        && progress.big_arm_defeated(old, current)
+        && !progress.has_fired(SplitTrigger::BigArm)
+    {
+        return Some(SplitTrigger::BigArm);
+    }
+
+    // Knuckles' story ends with his fight against Mecha Sonic, ahead of the ending sequence. The
+    // other characters fight Mecha Sonic in Sky Sanctuary too, but with the zone's own boss setting.
+    if settings.boss_mecha_sonic
+        && current.character == Character::Knuckles
+        && current.level == Levels::SkySanctuary
+        && !progress.has_split_on_boss(Levels::SkySanctuary)
+        && progress.boss_defeated(old, current)
+    {
+        return Some(SplitTrigger::Boss(Levels::SkySanctuary));
+    }
+
+    // Zone bosses split on the first boss defeated in the zone's last act, the zone's own boss
+    if settings.boss_split_enabled(current.level)
+        && !progress.has_split_on_boss(current.level)
+        && progress.boss_defeated(old, current)
+    {
+        return Some(SplitTrigger::Boss(current.level));
+    }
+
+    // New Game+ carries the emeralds over from the cleared save, so the emerald splits belong to
+    // the run that collected them
+    let emerald_splits = !progress.ng_plus;
+
+    // Checked ahead of the special stage splits, which would fire on the same tick
+    if emerald_splits
+        && super_emerald_collected(old, current)
+        && settings.super_emeralds.applies_to(current.super_emeralds)
+    {
+        return Some(SplitTrigger::SuperEmerald);
+    }
+
+    // Special stages are grouped according to the chosen interval, independently of the act being played
+    let interval = settings.special_stages.interval();
+    if emerald_splits
+        && interval != 0
+        && special_stage_completed(old, current)
+        && progress.special_stages_completed.is_multiple_of(interval)
+    {
+        return Some(SplitTrigger::SpecialStages);
+    }
+
+    // Tracked by the run's progress, so it isn't lost when a special stage split fires on the same tick
+    if emerald_splits
+        && settings.all_chaos_emeralds
+        && progress.all_chaos_emeralds
+        && !progress.has_fired(SplitTrigger::AllChaosEmeralds)
+    {
+        return Some(SplitTrigger::AllChaosEmeralds);
+    }
+
+    if settings.perfect_bonus && perfect_bonus_awarded(old, current) {
+        return Some(SplitTrigger::PerfectBonus);
+    }
+
+    if settings.super_transformation
+        && transformed(old, current)
+        && !progress.has_fired(SplitTrigger::Super)
+    {
+        return Some(SplitTrigger::Super);
+    }
+
+    if settings.hyper_transformation
+        && is_hyper(current)
+        && !progress.has_fired(SplitTrigger::Hyper)
+    {
+        return Some(SplitTrigger::Hyper);
+    }
+
+    if settings.death_egg_launch
+        && progress.death_egg_launched(old, current)
+        && !progress.has_fired(SplitTrigger::DeathEggLaunch)
+    {
+        return Some(SplitTrigger::DeathEggLaunch);
+    }
+
+    // The credits roll for every character and ending, whichever act the story ended with. When
+    // that act is due its split on the same tick, it goes first and the ending follows on the next.
+    if settings.ending
+        && current.level == Levels::Ending
+        && !progress.has_split(Levels::Ending)
+        && (old.level == Levels::Ending
+            || act_change_split(old, current, settings, progress).is_none())
+    {
+        return act_completed(Levels::Ending, settings, progress);
+    }
+
+    // If current act is AIZ1 (or an invalid stage) there's no need to continue
+    if current.level == Levels::AngelIslandAct1 {
+        return None;
+    }
+    // If current act is 21 (Sky Sanctuary) and the ending flag becomes true, trigger Knuckles' ending.
+    // Only his story ends there, the others carry on to the Death Egg.
+    else if settings.sky_sanctuary
+        && current.character == Character::Knuckles
+        && current.level == Levels::SkySanctuary
+        && current.game_ending_flag
+        && !old.game_ending_flag
+    {
+        return act_completed(Levels::SkySanctuary, settings, progress);
+    }
+
+    if settings.death_egg_2 && death_egg_2_time_bonus_drained(old, current) {
+        return act_completed(Levels::DeathEggAct2, settings, progress);
+    }
+
+    if settings.doomsday && doomsday_finished(old, current, settings, progress) {
+        return act_completed(Levels::DoomsDay, settings, progress);
+    }
+
+    // While an act reloads, the level ID can briefly read as garbage. The only act change that can
+    // legitimately happen at that point is leaving the act being reloaded, and it can't be cleared.
+    if let Some(restarting) = progress.restarting() {
+        if settings.ignore_act_restarts && old.level != restarting {
+            return None;
+        }
+    }
+
+    // Signpost mode: split on the act being cleared, ahead of the score tally. The act change that
+    // follows the tally then finds the act already split.
+    if settings.split_on_end_of_level
+        && progress.restarting().is_none()
+        && !old.end_of_level_flag
+        && current.end_of_level_flag
+        && settings.act_split_enabled_for(current.level, progress.character, current.emeralds)
+    {
+        return act_completed(current.level, settings, progress);
+    }
+
+    // Fade out mode: split as soon as the screen is fully black after the act has been cleared.
+    // Acts that transition seamlessly never fade out, so they still split on the act change below.
+    if settings.split_on_fade_out
+        && progress.restarting().is_none()
+        && old.end_of_level_flag
+        && !old.screen_faded_out
+        && current.screen_faded_out
+        && settings.act_split_enabled_for(current.level, progress.character, current.emeralds)
+    {
+        return act_completed(current.level, settings, progress);
+    }
+
+    act_change_split(old, current, settings, progress)
+}
+
+// Normal splitting condition: trigger a split whenever the act changes.
+// Strict mode extends the end of level requirement of AIZ1 to every act, so level ID glitches
+// and warps can't cause premature splits.
+fn act_change_split(
+    old: &GameState,
+    current: &GameState,
+    settings: &Settings,
+    progress: &RunProgress,
+) -> Option<SplitTrigger> {
+    let needs_end_of_level = settings.strict_end_of_level || old.level == Levels::AngelIslandAct1;
+    let enabled = old.level != current.level
+        && settings.act_split_enabled_for(old.level, progress.character, current.emeralds)
+        && (!needs_end_of_level || old.end_of_level_flag);
+
+    if enabled {
+        act_completed(old.level, settings, progress)
+    } else {
+        None
+    }
+}
+
+// Acts that already produced a split during the current run don't split again when revisited.
+// Neither do the acts the run is already past: getting back to them through a save reload, the
+// level select or a wrong warp would otherwise shift every split that follows. Randomized zone
+// orders have no acts to be past, and only rely on the first rule.
+fn act_completed(
+    level: Levels,
+    settings: &Settings,
+    progress: &RunProgress,
+) -> Option<SplitTrigger> {
+    if progress.has_split(level) || (!settings.order_independent && progress.has_split_past(level))
+    {
+        None
+    } else {
+        Some(SplitTrigger::Act(level))
+    }
+}
+
+// The Doomsday split defaults to the ending starting, like any other act change. The earlier
+// points follow the boss' last hit, on the same tick or once the screen has faded out.
+fn doomsday_finished(
+    old: &GameState,
+    current: &GameState,
+    settings: &Settings,
+    progress: &RunProgress,
+) -> bool {
+    if current.level != Levels::DoomsDay {
+        return false;
+    }
+
+    match settings.doomsday_split {
+        DoomsdaySplit::Ending => false,
+        DoomsdaySplit::BossDefeated => progress.boss_defeated(old, current),
+        DoomsdaySplit::FadeOut => {
+            progress.doomsday_boss_defeated() && !old.screen_faded_out && current.screen_faded_out
+        }
+    }
+}
+
+/// Special Trigger for Death Egg Zone Act 2 in Act 1: in this case a split needs to be triggered when the Time Bonus drops to zero, in accordance to speedrun.com rulings.
+///
+/// The expected sequence is the end of level flag getting set while in DEZ2, followed by the time bonus
+/// counting down to zero during the tally. A time bonus that is already zero on the first tick of the
+/// tally (eg. after a very slow act) never satisfies this rule, and the act change split applies instead.
+pub fn death_egg_2_time_bonus_drained(old: &GameState, current: &GameState) -> bool {
+    old.level == Levels::DeathEggAct2
+        && old.time_bonus != 0
+        && current.time_bonus == 0
+        && current.end_of_level_flag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{STATE_INGAME, STATE_LOADING};
+    use asr::settings::Gui;
+
+    fn in_level(level: Levels) -> GameState {
+        let mut state = GameState::default();
+        state.level = level;
+        state.state = STATE_LOADING;
+        state.game_mode = STATE_INGAME;
+        state
+    }
+
+    struct Case {
+        name: &'static str,
+        old: GameState,
+        current: GameState,
+        settings: fn(&mut Settings),
+        progress: RunProgress,
+        expected: Option<SplitTrigger>,
+    }
+
+    fn defaults(_: &mut Settings) {}
+
+    fn cases() -> [Case; 13] {
+        let dez2_tally = |time_bonus| {
+            let mut state = in_level(Levels::DeathEggAct2);
+            state.end_of_level_flag = true;
+            state.time_bonus = time_bonus;
+            state
+        };
+        let sky_sanctuary = |character, game_ending_flag| {
+            let mut state = in_level(Levels::SkySanctuary);
+            state.character = character;
+            state.game_ending_flag = game_ending_flag;
+            state
+        };
+        let angel_island_1 = |end_of_level_flag| {
+            let mut state = in_level(Levels::AngelIslandAct1);
+            state.end_of_level_flag = end_of_level_flag;
+            state
+        };
+        let restarting = RunProgress {
+            restarting: Some(Levels::HydrocityAct1),
+            ..Default::default()
+        };
+        let doomsday_split = RunProgress {
+            fired_splits: 1 << Levels::DoomsDay as u32,
+            ..Default::default()
+        };
+        let with_ending = |settings: &mut Settings| settings.ending = true;
+
+        [
+            Case {
+                name: "DEZ2 time bonus drained",
+                old: dez2_tally(100),
+                current: dez2_tally(0),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: Some(SplitTrigger::Act(Levels::DeathEggAct2)),
+            },
+            Case {
+                name: "DEZ2 time bonus still counting down",
+                old: dez2_tally(200),
+                current: dez2_tally(100),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: None,
+            },
+            Case {
+                name: "DEZ2 time bonus drained with the act split disabled",
+                old: dez2_tally(100),
+                current: dez2_tally(0),
+                settings: |settings| settings.death_egg_2 = false,
+                progress: RunProgress::default(),
+                expected: None,
+            },
+            Case {
+                name: "Knuckles' ending in Sky Sanctuary",
+                old: sky_sanctuary(Character::Knuckles, false),
+                current: sky_sanctuary(Character::Knuckles, true),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: Some(SplitTrigger::Act(Levels::SkySanctuary)),
+            },
+            Case {
+                name: "Ending flag in Sky Sanctuary for Sonic",
+                old: sky_sanctuary(Character::Sonic, false),
+                current: sky_sanctuary(Character::Sonic, true),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: None,
+            },
+            Case {
+                name: "AIZ1 left after being cleared",
+                old: angel_island_1(true),
+                current: in_level(Levels::AngelIslandAct2),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: Some(SplitTrigger::Act(Levels::AngelIslandAct1)),
+            },
+            Case {
+                name: "AIZ1 left without being cleared",
+                old: angel_island_1(false),
+                current: in_level(Levels::AngelIslandAct2),
+                settings: defaults,
+                progress: RunProgress::default(),
+                expected: None,
+            },
+            Case {
+                name: "Garbage level IDs while an act reloads",
+                old: in_level(Levels::MarbleGardenAct2),
+                current: in_level(Levels::CarnivalNightAct1),
+                settings: defaults,
+                progress: restarting,
+                expected: None,
+            },
+            Case {
+                name: "Leaving the act being reloaded",
+                old: in_level(Levels::HydrocityAct1),
+                current: in_level(Levels::HydrocityAct2),
+                settings: defaults,
+                progress: restarting,
+                expected: Some(SplitTrigger::Act(Levels::HydrocityAct1)),
+            },
+            Case {
+                name: "Garbage level IDs with restarts not ignored",
+                old: in_level(Levels::MarbleGardenAct2),
+                current: in_level(Levels::CarnivalNightAct1),
+                settings: |settings| settings.ignore_act_restarts = false,
+                progress: restarting,
+                expected: Some(SplitTrigger::Act(Levels::MarbleGardenAct2)),
+            },
+            Case {
+                name: "Credits after Doomsday, both splits enabled",
+                old: in_level(Levels::DoomsDay),
+                current: in_level(Levels::Ending),
+                settings: with_ending,
+                progress: RunProgress::default(),
+                expected: Some(SplitTrigger::Act(Levels::DoomsDay)),
+            },
+            Case {
+                name: "Credits the tick after the Doomsday split",
+                old: in_level(Levels::Ending),
+                current: in_level(Levels::Ending),
+                settings: with_ending,
+                progress: doomsday_split,
+                expected: Some(SplitTrigger::Act(Levels::Ending)),
+            },
+            Case {
+                name: "Credits after Doomsday, only the ending enabled",
+                old: in_level(Levels::DoomsDay),
+                current: in_level(Levels::Ending),
+                settings: |settings| {
+                    settings.ending = true;
+                    settings.doomsday = false;
+                },
+                progress: RunProgress::default(),
+                expected: Some(SplitTrigger::Act(Levels::Ending)),
+            },
+        ]
+    }
+
+    #[test]
+    fn split_table() {
+        for case in cases() {
+            let mut settings = Settings::register();
+            (case.settings)(&mut settings);
+            assert_eq!(
+                split(&case.old, &case.current, &settings, &case.progress),
+                case.expected,
+                "{}",
+                case.name
+            );
+        }
+    }
+}
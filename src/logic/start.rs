@@ -0,0 +1,99 @@
+use super::{competition, in_demo, time_attack};
+use crate::{
+    game_state::{
+        GameState, SAVESLOTSTATE_INPROGRESS, SAVESLOTSTATE_NEWGAME, STATE_LEVELSELECT,
+        STATE_LOADING, STATE_SAVESELECT, STATE_TITLE,
+    },
+    save_slots::SAVE_SLOTS,
+    settings::Settings,
+};
+
+/// Zone IDs from this one onwards are the competition zones and bonus stages, which a game never starts in
+const COMPETITION_ZONES_START: u8 = 14;
+
+/// Zone ID of Mushroom Hill, where Sonic & Knuckles starts on its own
+const MUSHROOM_HILL_ZONE: u8 = 7;
+
+/// What caused the timer to be started
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartReason {
+    Competition,
+    TimeAttack,
+    SonicAndKnucklesAlone,
+    LevelSelect,
+    NoSave,
+    SaveInProgress,
+    NewGame,
+    NewGamePlus,
+}
+
+pub fn start(old: &GameState, current: &GameState, settings: &Settings) -> Option<StartReason> {
+    // Leaving the game on the title screen plays demos, which load levels like a game does
+    if in_demo(old) || in_demo(current) {
+        return None;
+    }
+
+    // Competition mode is timed race by race, and has its own menus
+    if current.competition_mode {
+        return (settings.competition_start && competition::start(old, current))
+            .then_some(StartReason::Competition);
+    }
+
+    if settings.time_attack {
+        return time_attack::start(old, current).then_some(StartReason::TimeAttack);
+    }
+
+    // Sonic & Knuckles alone has no data select screen, and goes straight from the title screen
+    // to Mushroom Hill
+    if settings.sonic_and_knuckles_alone {
+        return (old.state == STATE_TITLE
+            && current.state == STATE_LOADING
+            && current.zone == MUSHROOM_HILL_ZONE
+            && settings.start_character.allows(current.character))
+        .then_some(StartReason::SonicAndKnucklesAlone);
+    }
+
+    // Practice and category extensions launch the stage straight from the level select menu
+    if settings.start_level_select
+        && old.state == STATE_LEVELSELECT
+        && current.state == STATE_LOADING
+        && current.zone < COMPETITION_ZONES_START
+    {
+        return settings
+            .start_character
+            .allows(current.character)
+            .then_some(StartReason::LevelSelect);
+    }
+
+    if old.state == STATE_SAVESELECT && current.state == STATE_LOADING {
+        // Competition and Blue Sphere are entered from the entries past the save slots of the data
+        // select screen, and go through the same transition as an actual game start
+        if current.save_select as u32 > SAVE_SLOTS || current.zone >= COMPETITION_ZONES_START {
+            return None;
+        }
+
+        // The character is set as soon as a file is chosen, before the level starts loading
+        if !settings.start_character.allows(current.character) {
+            return None;
+        }
+
+        if !settings.save_slot_filter.allows(current.save_select) {
+            return None;
+        }
+
+        if current.save_select == 0 {
+            return settings.start_nosave.then_some(StartReason::NoSave);
+        } else if current.zone_select == 0 {
+            if old.save_slot == SAVESLOTSTATE_INPROGRESS {
+                return settings
+                    .start_no_clean_save
+                    .then_some(StartReason::SaveInProgress);
+            } else if old.save_slot == SAVESLOTSTATE_NEWGAME {
+                return settings.start_clean_save.then_some(StartReason::NewGame);
+            } else if settings.start_new_game_plus {
+                return Some(StartReason::NewGamePlus);
+            }
+        }
+    }
+    None
+}
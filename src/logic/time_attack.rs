@@ -0,0 +1,28 @@
+use super::{act_restart_began, SplitTrigger};
+use crate::game_state::{GameState, STATE_INGAME, STATE_LOADING, STATE_SAVESELECT, STATE_TITLE};
+
+// A.I.R.'s Time Attack menus are part of A.I.R. itself rather than the emulated game, so a Time
+// Attack attempt is only seen as an act being loaded from anywhere but the game itself or its own
+// menus. Each attempt covers a single act, from its load until it's cleared.
+fn act_loaded_from_menu(old: &GameState, current: &GameState) -> bool {
+    current.game_mode == STATE_LOADING
+        && !matches!(
+            old.game_mode,
+            STATE_INGAME | STATE_LOADING | STATE_SAVESELECT | STATE_TITLE
+        )
+}
+
+pub fn start(old: &GameState, current: &GameState) -> bool {
+    act_loaded_from_menu(old, current)
+}
+
+// Records are timed up to the moment the act is cleared, not to the tally
+pub fn split(old: &GameState, current: &GameState) -> Option<SplitTrigger> {
+    (!old.end_of_level_flag && current.end_of_level_flag)
+        .then_some(SplitTrigger::Act(current.level))
+}
+
+// Retrying reloads the act, and a new attempt can be started straight from the menu
+pub fn reset(old: &GameState, current: &GameState) -> bool {
+    act_restart_began(old, current) || act_loaded_from_menu(old, current)
+}
@@ -0,0 +1,315 @@
+use super::Memory;
+use crate::debug::{log, set_variable};
+use asr::{future::next_tick, settings, signature::Signature, timer, Address, FromEndian, Process};
+use bytemuck::CheckedBitPattern;
+use core::{cell::Cell, ops::RangeInclusive};
+
+/// The Windows build, also running as such under Wine and Proton, and the native Linux build
+const PROCESS_NAMES: [&str; 2] = ["Sonic3AIR.exe", "sonic3air_linux"];
+
+/// Memory layout of a release of the game, told apart by the size of its simulation memory range
+struct Layout {
+    name: &'static str,
+    simulation_ram_size: u64,
+    wram_offset: u64,
+}
+
+/// Layouts shipped by the releases known so far. A release changing its allocation keeps working
+/// through the ROM header detection until it gets an entry of its own here.
+const KNOWN_LAYOUTS: [Layout; 1] = [Layout {
+    name: "Windows release",
+    simulation_ram_size: 0x521000,
+    wram_offset: WRAM_OFFSET,
+}];
+
+/// The simulation memory range found, and how it was identified
+struct SimulationRange {
+    address: Address,
+    size: u64,
+    wram_offset: u64,
+    layout: &'static str,
+}
+
+/// Bounds for the size of the simulation memory range in releases that aren't known yet. The range
+/// holds the 4 MB ROM followed by the 64 KB of WRAM, plus whatever the engine adds around them.
+const SIMULATION_RAM_SIZE_BOUNDS: RangeInclusive<u64> = WRAM_OFFSET + 0x10000..=0x800000;
+
+/// Offset of the emulated 68k RAM inside the simulation memory range, for the known sizes
+const WRAM_OFFSET: u64 = 0x400020;
+
+/// The simulation memory holds the ROM, directly followed by the WRAM
+const ROM_SIZE: u64 = 0x400000;
+
+/// Where the ROM can start in a range of unknown size. Allocations this large get a range of their
+/// own, behind the heap's block header: the one of Windows (and Wine), or the smaller chunk header
+/// of glibc for the native Linux build.
+const ROM_OFFSETS: [u64; 2] = [WRAM_OFFSET - ROM_SIZE, 0x10];
+
+/// The ROM's header identifies ranges of unknown sizes
+const ROM_HEADER_OFFSET: u64 = 0x100;
+const ROM_HEADER_SYSTEM: &[u8; 4] = b"SEGA";
+
+/// Start of the ROM's header: the system name, padded to 16 characters, and the copyright notice.
+/// The ROM is loaded as is, so this doesn't depend on how the engine lays out its memory.
+const ROM_HEADER_SIGNATURE: Signature<23> =
+    Signature::new("53 45 47 41 20 47 45 4E 45 53 49 53 20 20 20 20 28 43 29 53 45 47 41");
+
+/// Ranges larger than this aren't scanned, as they can't be the simulation's allocation alone
+const MAX_SCANNED_RANGE_SIZE: u64 = 0x1000000;
+
+/// Ticks to wait between two attempts at finding the simulation again, once it moved
+const RESOLVE_RETRY_TICKS: u32 = 60;
+
+/// A running Sonic 3 A.I.R. process, along with the location of its emulated RAM
+pub struct Air {
+    process: Process,
+    wram_base: Cell<Address>,
+    /// Whether the WRAM base was found by the splitter, and can be found again if it moves. A
+    /// base set by hand is left as it is.
+    resolved: bool,
+    resolve_cooldown: Cell<u32>,
+    failed_reads: Cell<u32>,
+}
+
+impl Air {
+    pub fn attach_process() -> Option<Process> {
+        PROCESS_NAMES.into_iter().find_map(Process::attach)
+    }
+
+    /// Attaches to the given instance of the game, when several of them are running, in the order
+    /// the runtime lists them
+    pub fn attach_instance(instance: usize) -> Option<Process> {
+        PROCESS_NAMES.into_iter().find_map(|name| {
+            let pids = Process::list_by_name::<4>(name)?;
+            Process::attach_by_pid(*pids.get(instance)?)
+        })
+    }
+
+    /// Waits for the simulation to be allocated. Returns `None` if the process closes in the meantime.
+    /// The addresses found are published as variables, to help diagnosing hooking issues on unusual setups.
+    pub async fn hook(process: Process) -> Option<Self> {
+        loop {
+            if !process.is_open() {
+                return None;
+            }
+
+            if let Some(WramOverride::Address(wram_base)) = WramOverride::load() {
+                set_variable!("WRAM base", "{:#X} (override)", wram_base.value());
+                return Some(Self::new(process, wram_base, false));
+            }
+
+            if let Some(range) = simulation_range(&process) {
+                let (wram_base, resolved) = match WramOverride::load() {
+                    Some(WramOverride::Offset(offset)) => (range.address + offset, false),
+                    _ => (range.address + range.wram_offset, true),
+                };
+
+                set_variable!(
+                    "Simulation memory range",
+                    "{:#X} ({:#X} bytes)",
+                    range.address.value(),
+                    range.size
+                );
+                set_variable!("WRAM base", "{:#X}", wram_base.value());
+                timer::set_variable("Memory layout", range.layout);
+                // The size of the executable tells builds apart, for adding them to the known layouts
+                if let Some(size) = PROCESS_NAMES
+                    .into_iter()
+                    .find_map(|name| process.get_module_size(name).ok())
+                {
+                    set_variable!("Executable size", "{:#X}", size);
+                }
+
+                return Some(Self::new(process, wram_base, resolved));
+            }
+
+            next_tick().await;
+        }
+    }
+
+    /// Hooks to the process right away, if the simulation is already allocated
+    pub fn hook_now(process: Process) -> Option<Self> {
+        let range = simulation_range(&process)?;
+        Some(Self::new(process, range.address + range.wram_offset, true))
+    }
+
+    fn new(process: Process, wram_base: Address, resolved: bool) -> Self {
+        Self {
+            process,
+            wram_base: Cell::new(wram_base),
+            resolved,
+            resolve_cooldown: Cell::new(0),
+            failed_reads: Cell::new(0),
+        }
+    }
+
+    pub const fn process(&self) -> &Process {
+        &self.process
+    }
+
+    /// Returns the number of reads that failed since the last call
+    pub fn take_failed_reads(&self) -> u32 {
+        self.failed_reads.take()
+    }
+}
+
+impl Air {
+    /// Makes sure the WRAM base still follows the ROM, as the engine can reallocate the simulation
+    /// without the process closing. Meant to be called once per tick.
+    pub fn update(&self) {
+        if !self.resolved || self.rom_precedes_wram() {
+            return;
+        }
+
+        // Ranges get scanned while looking for the simulation again, so it's only retried now and then
+        let cooldown = self.resolve_cooldown.get();
+        if cooldown != 0 {
+            self.resolve_cooldown.set(cooldown - 1);
+            return;
+        }
+        self.resolve_cooldown.set(RESOLVE_RETRY_TICKS);
+
+        if let Some(range) = simulation_range(&self.process) {
+            let wram_base = range.address + range.wram_offset;
+            log!(
+                "The simulation moved, WRAM base is now {:#X}",
+                wram_base.value()
+            );
+            set_variable!("WRAM base", "{:#X}", wram_base.value());
+            timer::set_variable("Memory layout", range.layout);
+            self.wram_base.set(wram_base);
+        }
+    }
+
+    fn rom_precedes_wram(&self) -> bool {
+        let header = self.wram_base.get().value().wrapping_sub(ROM_SIZE) + ROM_HEADER_OFFSET;
+        self.process
+            .read::<[u8; 4]>(Address::new(header))
+            .is_ok_and(|header| &header == ROM_HEADER_SYSTEM)
+    }
+
+    /// Reads a whole block of WRAM at once
+    pub fn read_block(&self, offset: u32, buf: &mut [u8]) -> bool {
+        let ok = self
+            .process
+            .read_into_buf(self.wram_base.get() + offset, buf)
+            .is_ok();
+        if !ok {
+            self.failed_reads.set(self.failed_reads.get() + 1);
+        }
+        ok
+    }
+}
+
+impl Memory for Air {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        match self.process.read::<T>(self.wram_base.get() + offset) {
+            Ok(value) => Some(value.from_be()),
+            Err(_) => {
+                self.failed_reads.set(self.failed_reads.get() + 1);
+                None
+            }
+        }
+    }
+}
+
+/// Key of the settings map holding a manually set WRAM base, as an escape hatch for builds the
+/// detection doesn't handle yet. It's meant to be edited in the layout or splits file, as a
+/// hexadecimal value: either an absolute address, or an offset into the simulation memory range
+/// when prefixed with `+`.
+const KEY_WRAM_OVERRIDE: &str = "wram_base_override";
+
+enum WramOverride {
+    Address(Address),
+    Offset(u64),
+}
+
+impl WramOverride {
+    fn load() -> Option<Self> {
+        let value = settings::Map::load()
+            .get(KEY_WRAM_OVERRIDE)?
+            .get_array_string::<32>()?
+            .ok()?;
+        let value = value.trim();
+
+        let parse = |hex: &str| {
+            let hex = hex.trim_start_matches("0x").trim_start_matches("0X");
+            u64::from_str_radix(hex, 16).ok()
+        };
+
+        match value.strip_prefix('+') {
+            Some(offset) => parse(offset).map(Self::Offset),
+            None => parse(value).map(|address| Self::Address(Address::new(address))),
+        }
+    }
+}
+
+/// Looks for the memory range holding the simulation and returns its address and size, along
+/// with the offset of the WRAM inside of it
+fn simulation_range(process: &Process) -> Option<SimulationRange> {
+    scan_rom_header(process).or_else(|| range_by_size(process))
+}
+
+/// Scans the memory for the ROM's header, which is followed by the rest of the ROM and the WRAM
+fn scan_rom_header(process: &Process) -> Option<SimulationRange> {
+    process.memory_ranges().find_map(|range| {
+        let (address, size) = range.range().ok()?;
+        if size < *SIMULATION_RAM_SIZE_BOUNDS.start() || size > MAX_SCANNED_RANGE_SIZE {
+            return None;
+        }
+
+        let header = ROM_HEADER_SIGNATURE.scan_process_range(process, (address, size))?;
+        let rom_offset = (header.value() - address.value()).checked_sub(ROM_HEADER_OFFSET)?;
+        let wram_offset = rom_offset + ROM_SIZE;
+
+        // The whole WRAM needs to fit in the range, or the match was something else entirely
+        (wram_offset + 0x10000 <= size).then_some(SimulationRange {
+            address,
+            size,
+            wram_offset,
+            layout: "Found by ROM header signature",
+        })
+    })
+}
+
+/// Falls back to identifying the simulation's range by its size
+fn range_by_size(process: &Process) -> Option<SimulationRange> {
+    // Known sizes are trusted as they are. Anything else within the bounds needs to hold the ROM,
+    // so an unrelated allocation of a similar size doesn't get picked up. This also covers the
+    // platforms where the allocator rounds the range to a different size.
+    let candidates = || {
+        process.memory_ranges().filter_map(|range| {
+            let size = range.size().ok()?;
+            Some((range.address().ok()?, size))
+        })
+    };
+    candidates()
+        .find_map(|(address, size)| {
+            let layout = KNOWN_LAYOUTS
+                .iter()
+                .find(|layout| layout.simulation_ram_size == size)?;
+            Some(SimulationRange {
+                address,
+                size,
+                wram_offset: layout.wram_offset,
+                layout: layout.name,
+            })
+        })
+        .or_else(|| {
+            candidates()
+                .filter(|(_, size)| SIMULATION_RAM_SIZE_BOUNDS.contains(size))
+                .find_map(|(address, size)| {
+                    let rom_offset = ROM_OFFSETS.into_iter().find(|&rom_offset| {
+                        process
+                            .read::<[u8; 4]>(address + rom_offset + ROM_HEADER_OFFSET)
+                            .is_ok_and(|header| &header == ROM_HEADER_SYSTEM)
+                    })?;
+                    Some(SimulationRange {
+                        address,
+                        size,
+                        wram_offset: rom_offset + ROM_SIZE,
+                        layout: "Unknown release, found by ROM header",
+                    })
+                })
+        })
+}
@@ -0,0 +1,65 @@
+use super::Memory;
+use asr::{emulator::genesis::Emulator, future::next_tick, FromEndian};
+use bytemuck::CheckedBitPattern;
+use core::cell::Cell;
+
+/// A Sega Genesis emulator running Sonic 3 & Knuckles. The WRAM layout is the same as in
+/// Sonic 3 A.I.R.; finding where each emulator keeps it, and dealing with the emulators storing it
+/// byte swapped, is left to the emulator helper.
+pub struct Genesis {
+    emulator: Emulator,
+    failed_reads: Cell<u32>,
+}
+
+impl Genesis {
+    /// Attaches to the first supported emulator that can be found running
+    pub fn attach_emulator() -> Option<Emulator> {
+        Emulator::attach()
+    }
+
+    /// Waits for the emulated RAM to be located, which only happens once a game is loaded.
+    /// Returns `None` if the emulator closes in the meantime.
+    pub async fn hook(emulator: Emulator) -> Option<Self> {
+        loop {
+            if !emulator.is_open() {
+                return None;
+            }
+
+            if emulator.update() {
+                return Some(Self {
+                    emulator,
+                    failed_reads: Cell::new(0),
+                });
+            }
+
+            next_tick().await;
+        }
+    }
+
+    /// Follows the emulated RAM around, as emulators reallocate it when a game gets reloaded
+    pub fn update(&self) {
+        self.emulator.update();
+    }
+
+    pub const fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    /// Returns the number of reads that failed since the last call
+    pub fn take_failed_reads(&self) -> u32 {
+        self.failed_reads.take()
+    }
+}
+
+impl Memory for Genesis {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        // The helper already converts the value from the console's byte order
+        match self.emulator.read::<T>(offset) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.failed_reads.set(self.failed_reads.get() + 1);
+                None
+            }
+        }
+    }
+}
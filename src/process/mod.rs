@@ -0,0 +1,216 @@
+#[cfg(feature = "air")]
+pub mod air;
+#[cfg(feature = "genesis")]
+pub mod genesis;
+
+use asr::{future::next_tick, FromEndian};
+use bytemuck::CheckedBitPattern;
+use core::future::Future;
+
+/// Read access to the emulated 68k RAM. Offsets are relative to the start of WRAM and values are
+/// stored big endian, like on the original hardware, so implementors are expected to return them
+/// already converted to native endianness.
+///
+/// The game state is built exclusively through this trait, which allows feeding it scripted
+/// memory snapshots instead of a live process.
+pub trait Memory {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T>;
+}
+
+/// A raw dump of the emulated RAM, as it would be read from the game
+impl Memory for [u8] {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        let offset = offset as usize;
+        let bytes = self.get(offset..offset + core::mem::size_of::<T>())?;
+        bytemuck::checked::try_pod_read_unaligned::<T>(bytes)
+            .ok()
+            .map(|value| value.from_be())
+    }
+}
+
+/// A whole WRAM, filled in value by value to stand in for the game in tests
+#[cfg(test)]
+pub struct Wram([u8; 0x10000]);
+
+#[cfg(test)]
+impl Wram {
+    pub const fn new() -> Self {
+        Self([0; 0x10000])
+    }
+
+    /// Writes the bytes of a value, big endian as the 68k stores them
+    pub fn write(&mut self, offset: u32, bytes: &[u8]) -> &mut Self {
+        let offset = offset as usize;
+        self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+}
+
+#[cfg(test)]
+impl Memory for Wram {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        self.0[..].read(offset)
+    }
+}
+
+/// Start of the block of WRAM read at once every tick. Everything the game state is built from lies
+/// between this and the end of WRAM.
+const SNAPSHOT_START: u32 = 0xB000;
+const SNAPSHOT_SIZE: usize = 0x10000 - SNAPSHOT_START as usize;
+
+/// WRAM as it was when the snapshot got taken, so every value read from it comes from the same
+/// frame. Reads outside of the block, or on targets that couldn't provide it, go to the target.
+pub struct Snapshot<'a> {
+    target: &'a Target,
+    bytes: Option<[u8; SNAPSHOT_SIZE]>,
+}
+
+impl Memory for Snapshot<'_> {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        match (&self.bytes, offset.checked_sub(SNAPSHOT_START)) {
+            (Some(bytes), Some(offset)) => bytes[..].read(offset),
+            _ => self.target.read(offset),
+        }
+    }
+}
+
+/// One of the supported targets, hooked and providing access to the game's emulated RAM.
+///
+/// The start, split and reset logic only ever sees the game through the `GameState` built from
+/// the `Memory` trait, so each target is nothing more than a provider of that memory.
+pub enum Target {
+    #[cfg(feature = "air")]
+    Air(air::Air),
+    #[cfg(feature = "genesis")]
+    Genesis(genesis::Genesis),
+}
+
+impl Target {
+    /// Hooks to the first supported target that can be found. Once a target closes, calling this
+    /// again picks up whichever supported target is running next, with its own memory layout.
+    pub async fn attach(instance: usize) -> Self {
+        loop {
+            #[cfg(feature = "air")]
+            if let Some(process) = attach_air(instance) {
+                if let Some(air) = air::Air::hook(process).await {
+                    return Self::Air(air);
+                }
+            }
+
+            // Emulators can only be told apart from each other, not the game they run, so only the
+            // first one found is ever followed
+            #[cfg(feature = "genesis")]
+            if instance == 0 {
+                if let Some(emulator) = genesis::Genesis::attach_emulator() {
+                    if let Some(genesis) = genesis::Genesis::hook(emulator).await {
+                        return Self::Genesis(genesis);
+                    }
+                }
+            }
+
+            next_tick().await;
+        }
+    }
+
+    /// Makes a single attempt at hooking to the given instance of a supported target, for targets
+    /// that get polled alongside the main one
+    #[cfg_attr(not(feature = "air"), allow(unused_variables))]
+    pub fn attach_now(instance: usize) -> Option<Self> {
+        #[cfg(feature = "air")]
+        if let Some(air) = air::Air::attach_instance(instance).and_then(air::Air::hook_now) {
+            return Some(Self::Air(air));
+        }
+
+        None
+    }
+
+    pub fn is_open(&self) -> bool {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.process().is_open(),
+            #[cfg(feature = "genesis")]
+            Self::Genesis(genesis) => genesis.emulator().is_open(),
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(_) => "Sonic 3 A.I.R.",
+            #[cfg(feature = "genesis")]
+            Self::Genesis(_) => "Sega Genesis emulator",
+        }
+    }
+
+    /// Runs the future until the target closes
+    pub async fn until_closes<F: Future>(&self, future: F) -> Option<F::Output> {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.process().until_closes(future).await,
+            #[cfg(feature = "genesis")]
+            Self::Genesis(genesis) => genesis.emulator().until_closes(future).await,
+        }
+    }
+
+    /// Returns the number of memory reads that failed since the last call
+    pub fn take_failed_reads(&self) -> u32 {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.take_failed_reads(),
+            #[cfg(feature = "genesis")]
+            Self::Genesis(genesis) => genesis.take_failed_reads(),
+        }
+    }
+
+    /// Takes a snapshot of the part of WRAM the game state is read from, with a single read on the
+    /// targets that allow it
+    pub fn snapshot(&self) -> Snapshot<'_> {
+        #[cfg_attr(not(feature = "air"), allow(unused_mut))]
+        let mut bytes = [0; SNAPSHOT_SIZE];
+        let read = match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.read_block(SNAPSHOT_START, &mut bytes),
+            // Some emulators keep the RAM byte swapped, which the emulator helper only takes care
+            // of for values read one by one
+            #[cfg(feature = "genesis")]
+            Self::Genesis(_) => false,
+        };
+
+        Snapshot {
+            target: self,
+            bytes: read.then_some(bytes),
+        }
+    }
+
+    /// Keeps track of the emulated RAM moving. Meant to be called once per tick, before reading the
+    /// game's state.
+    pub fn update(&self) {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.update(),
+            #[cfg(feature = "genesis")]
+            Self::Genesis(genesis) => genesis.update(),
+        }
+    }
+}
+
+// Instances are picked from the same list the second instance is, so both never end up being the
+// same process. Attaching by name remains as a fallback for the first one.
+#[cfg(feature = "air")]
+fn attach_air(instance: usize) -> Option<asr::Process> {
+    air::Air::attach_instance(instance).or_else(|| match instance {
+        0 => air::Air::attach_process(),
+        _ => None,
+    })
+}
+
+impl Memory for Target {
+    fn read<T: CheckedBitPattern + FromEndian>(&self, offset: u32) -> Option<T> {
+        match self {
+            #[cfg(feature = "air")]
+            Self::Air(air) => air.read(offset),
+            #[cfg(feature = "genesis")]
+            Self::Genesis(genesis) => genesis.read(offset),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    debug::set_variable,
+    game_state::{
+        Character, SAVESLOTSTATE_COMPLETE, SAVESLOTSTATE_COMPLETEWITHEMERALDS,
+        SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS, SAVESLOTSTATE_NEWGAME,
+    },
+    process::Memory,
+};
+use asr::timer;
+
+/// Number of save slots available on the data select screen. Every save select value past them is
+/// one of the extra entries, Competition and Blue Sphere, which have no save data. These are the
+/// original game's slots: no location is known for save data of any slot past them, so starts and
+/// resets from such slots aren't supported.
+pub const SAVE_SLOTS: u32 = 8;
+
+/// Number of extra entries following the save slots on the data select screen, Competition and
+/// Blue Sphere
+pub const EXTRA_ENTRIES: u32 = 2;
+
+/// Whether the save select value is one of the save slots, as opposed to no save or the extra
+/// entries that follow the slots
+pub const fn is_save_slot(save_select: u8) -> bool {
+    save_select != 0 && save_select as u32 <= SAVE_SLOTS
+}
+
+/// Address of the slot's state in the save data, the slot being numbered from 1
+pub const fn state_address(slot: u32) -> u32 {
+    0xE6AC + 0xA * (slot - 1)
+}
+
+/// Address of the zone shown by the slot's object on the data select screen
+pub const fn zone_address(slot: u32) -> u32 {
+    0xB15F + 0x4A * (slot - 1)
+}
+
+/// Contents of a save file, as shown on the data select screen
+#[derive(Clone, Copy, Debug)]
+pub struct SaveSlot {
+    pub state: u8,
+    pub character: Character,
+    pub zone: u8,
+    pub emeralds: u8,
+}
+
+impl SaveSlot {
+    /// Reads the given slot, numbered from 1 like the game's save select value
+    pub fn read(memory: &(impl Memory + ?Sized), slot: u32) -> Option<Self> {
+        // Each slot takes 0xA bytes in the save data. The emeralds are stored as a word with two
+        // bits per emerald, set once the emerald has been collected.
+        let base = state_address(slot);
+        let emeralds = memory.read::<u16>(base + 6)?;
+
+        Some(Self {
+            state: memory.read::<u8>(base)?,
+            character: Character::from_player_mode(memory.read::<u8>(base + 2)? as u16 >> 4),
+            zone: memory.read::<u8>(zone_address(slot))?,
+            emeralds: (0..7)
+                .filter(|emerald| emeralds >> (emerald * 2) & 0b11 != 0)
+                .count() as u8,
+        })
+    }
+
+    /// Publishes a short description of every save slot, for overlays to show what's on the file
+    /// being selected
+    pub fn publish_all(memory: &(impl Memory + ?Sized)) {
+        const KEYS: [&str; SAVE_SLOTS as usize] = [
+            "Save slot 1",
+            "Save slot 2",
+            "Save slot 3",
+            "Save slot 4",
+            "Save slot 5",
+            "Save slot 6",
+            "Save slot 7",
+            "Save slot 8",
+        ];
+
+        for (slot, key) in (1..=SAVE_SLOTS).zip(KEYS) {
+            match Self::read(memory, slot) {
+                Some(save) if save.state == SAVESLOTSTATE_NEWGAME => {
+                    timer::set_variable(key, "New game")
+                }
+                Some(save) => set_variable!(
+                    key,
+                    "{:?}, {}, {} emeralds{}",
+                    save.character,
+                    save.zone_name(),
+                    save.emeralds,
+                    match save.state {
+                        SAVESLOTSTATE_COMPLETE
+                        | SAVESLOTSTATE_COMPLETEWITHEMERALDS
+                        | SAVESLOTSTATE_COMPLETEWITHSUPEREMERALDS => " (Clear)",
+                        _ => "",
+                    }
+                ),
+                None => timer::set_variable(key, "-"),
+            }
+        }
+    }
+
+    pub const fn zone_name(&self) -> &'static str {
+        match self.zone {
+            0 => "Angel Island Zone",
+            1 => "Hydrocity Zone",
+            2 => "Marble Garden Zone",
+            3 => "Carnival Night Zone",
+            4 => "Flying Battery Zone",
+            5 => "Ice Cap Zone",
+            6 => "Launch Base Zone",
+            7 => "Mushroom Hill Zone",
+            8 => "Sandopolis Zone",
+            9 => "Lava Reef Zone",
+            10 => "Sky Sanctuary Zone",
+            11 => "Death Egg Zone",
+            12 => "Doomsday Zone",
+            _ => "Unknown zone",
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{debug::NOMINAL_TICK_RATE, game_state::GameState, process::Target};
+
+/// A second instance of the game, running alongside the one driving the timer, for races and
+/// commentary on a restream setup. It's only ever read to expose its player's progress.
+pub struct SecondInstance {
+    target: Option<Target>,
+    state: GameState,
+    retry_ticks: u32,
+}
+
+impl SecondInstance {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            state: GameState::default(),
+            retry_ticks: 0,
+        }
+    }
+
+    /// Keeps the given instance hooked and publishes its player's progress
+    pub fn tick(&mut self, instance: usize) {
+        if self.target.as_ref().is_some_and(|target| !target.is_open()) {
+            self.target = None;
+        }
+
+        if self.target.is_none() {
+            // Attaching is comparatively expensive, so it's only attempted about once per second
+            if self.retry_ticks != 0 {
+                self.retry_ticks -= 1;
+                return;
+            }
+            self.retry_ticks = NOMINAL_TICK_RATE;
+            self.target = Target::attach_now(instance);
+        }
+
+        if let Some(target) = &self.target {
+            self.state = GameState::read(target, &self.state);
+            self.state.publish_player(instance + 1);
+        }
+    }
+}
@@ -0,0 +1,710 @@
+use crate::{
+    game_state::{Character, Levels},
+    save_slots::SAVE_SLOTS,
+};
+use asr::settings::{gui::Title, Gui};
+
+#[derive(Gui)]
+pub struct Settings {
+    #[heading_level = 0]
+    /// Category
+    _category: Title,
+    /// Category preset, overriding the mode options below and the character presets
+    pub category: Category,
+    #[default = false]
+    /// Time Attack: start when an act is loaded from A.I.R.'s menu, split once it's cleared, reset on retries
+    pub time_attack: bool,
+    #[default = false]
+    /// Sonic 3 alone: the run ends with Big Arm's defeat in Launch Base Zone - Act 2
+    pub sonic_3_alone: bool,
+    #[default = false]
+    /// Sonic & Knuckles alone: start from the title screen in Mushroom Hill, and end with the character's story
+    pub sonic_and_knuckles_alone: bool,
+    #[heading_level = 0]
+    /// Start
+    _start: Title,
+    #[default = true]
+    /// Auto start (No save)
+    pub start_nosave: bool,
+    #[default = true]
+    /// Auto start (Clean save)
+    pub start_clean_save: bool,
+    #[default = true]
+    /// Auto start (Angel Island Zone - No clean save)
+    pub start_no_clean_save: bool,
+    #[default = true]
+    /// Auto start (New Game+)
+    pub start_new_game_plus: bool,
+    #[default = false]
+    /// Auto start (Stage launched from the level select)
+    pub start_level_select: bool,
+    #[default = false]
+    /// Only auto start once, until the timer is reset manually
+    pub start_only_once: bool,
+    /// Only auto start with this character
+    pub start_character: StartCharacter,
+    /// Only auto start and reset from this save slot
+    pub save_slot_filter: SaveSlotFilter,
+    /// When to auto start
+    pub start_timing: StartTiming,
+    #[default = false]
+    /// Marathon mode: once the last split is done, the next game started begins a new run
+    pub marathon_mode: bool,
+    #[default = false]
+    /// In marathon mode, never auto reset mid-run
+    pub marathon_skip_reset: bool,
+    #[heading_level = 0]
+    /// Reset
+    _reset: Title,
+    #[default = true]
+    /// Auto reset
+    pub reset: bool,
+    #[default = true]
+    /// Auto reset when a new game without a save is started
+    pub reset_on_no_save_start: bool,
+    #[default = true]
+    /// Auto reset when a save slot is cleared
+    pub reset_on_save_wipe: bool,
+    #[default = false]
+    /// Only reset on a cleared save slot if it's the one the run was started on
+    pub reset_same_slot_only: bool,
+    /// Past this run duration, only reset when a save slot is cleared or the console is reset
+    pub reset_minimum_time: ResetMinimumTime,
+    #[default = false]
+    /// Auto reset on Game Over, before the first split
+    pub reset_on_game_over: bool,
+    #[default = false]
+    /// Auto reset when the console is reset or the ROM reloaded
+    pub reset_on_console_reset: bool,
+    #[default = false]
+    /// Auto reset when restarting Angel Island Zone - Act 1 from the pause menu
+    pub reset_on_angel_island_restart: bool,
+    #[heading_level = 0]
+    /// Act splits
+    _act_splits: Title,
+    #[default = true]
+    /// Angel Island Zone - Act 1
+    pub angel_island_1: bool,
+    #[default = true]
+    /// Angel Island Zone - Act 2
+    pub angel_island_2: bool,
+    #[default = true]
+    /// Hydrocity Zone - Act 1
+    pub hydrocity_1: bool,
+    #[default = true]
+    /// Hydrocity Zone - Act 2
+    pub hydrocity_2: bool,
+    #[default = true]
+    /// Marble Garden Zone - Act 1
+    pub marble_garden_1: bool,
+    #[default = true]
+    /// Marble Garden Zone - Act 2
+    pub marble_garden_2: bool,
+    #[default = true]
+    /// Carnival Night Zone - Act 1
+    pub carnival_night_1: bool,
+    #[default = true]
+    /// Carnival Night Zone - Act 2
+    pub carnival_night_2: bool,
+    #[default = true]
+    /// Ice Cap Zone - Act 1
+    pub ice_cap_1: bool,
+    #[default = true]
+    /// Ice Cap Zone - Act 2
+    pub ice_cap_2: bool,
+    #[default = true]
+    /// Launch Base Zone - Act 1
+    pub launch_base_1: bool,
+    #[default = true]
+    /// Launch Base Zone - Act 2
+    pub launch_base_2: bool,
+    #[default = true]
+    /// Mushroom Hill Zone - Act 1
+    pub mushroom_hill_1: bool,
+    #[default = true]
+    /// Mushroom Hill Zone - Act 2
+    pub mushroom_hill_2: bool,
+    #[default = true]
+    /// Flying Battery Zone - Act 1
+    pub flying_battery_1: bool,
+    #[default = true]
+    /// Flying Battery Zone - Act 2
+    pub flying_battery_2: bool,
+    #[default = true]
+    /// Sandopolis Zone - Act 1
+    pub sandopolis_1: bool,
+    #[default = true]
+    /// Sandopolis Zone - Act 2
+    pub sandopolis_2: bool,
+    #[default = true]
+    /// Lava Reef Zone - Act 1
+    pub lava_reef_1: bool,
+    #[default = true]
+    /// Lava Reef Zone - Act 2
+    pub lava_reef_2: bool,
+    #[default = true]
+    /// Hidden Palace Zone
+    pub hidden_palace: bool,
+    #[default = true]
+    /// Sky Sanctuary Zone
+    pub sky_sanctuary: bool,
+    #[default = true]
+    /// Death Egg Zone - Act 1
+    pub death_egg_1: bool,
+    #[default = true]
+    /// Death Egg Zone - Act 2
+    pub death_egg_2: bool,
+    #[default = true]
+    /// Doomsday Zone
+    pub doomsday: bool,
+    #[default = false]
+    /// Ending (when the credits start, whatever act the story ended with)
+    pub ending: bool,
+    /// Moment the Doomsday Zone split fires at
+    pub doomsday_split: DoomsdaySplit,
+    #[default = false]
+    /// Zone splits: only split at the end of each zone, ignoring the first act of every zone above
+    pub zone_splits: bool,
+    #[default = false]
+    /// Apply the split preset of the character the run is started with, over the act splits above
+    pub character_presets: bool,
+    #[default = false]
+    /// Order independent mode, for zone randomizers: any enabled act splits once, in any order
+    pub order_independent: bool,
+    #[default = false]
+    /// Strict mode: only split on an act change after the act has been cleared
+    pub strict_end_of_level: bool,
+    #[default = true]
+    /// Ignore act restarts (deaths and A.I.R.'s restart act feature) for act splits
+    pub ignore_act_restarts: bool,
+    #[default = false]
+    /// Split when the screen finishes fading out after clearing an act, instead of on the act change
+    pub split_on_fade_out: bool,
+    #[default = false]
+    /// Split as soon as the act is cleared (signpost landing, capsule opening), before the tally
+    pub split_on_end_of_level: bool,
+    #[heading_level = 0]
+    /// Boss splits
+    _boss_splits: Title,
+    #[default = false]
+    /// Angel Island Zone boss
+    pub boss_angel_island: bool,
+    #[default = false]
+    /// Hydrocity Zone boss
+    pub boss_hydrocity: bool,
+    #[default = false]
+    /// Marble Garden Zone boss
+    pub boss_marble_garden: bool,
+    #[default = false]
+    /// Carnival Night Zone boss
+    pub boss_carnival_night: bool,
+    #[default = false]
+    /// Ice Cap Zone boss
+    pub boss_ice_cap: bool,
+    #[default = false]
+    /// Launch Base Zone boss
+    pub boss_launch_base: bool,
+    #[default = false]
+    /// Big Arm, ending the Sonic 3 half of the game in Launch Base Zone - Act 2
+    pub boss_big_arm: bool,
+    #[default = false]
+    /// Mushroom Hill Zone boss
+    pub boss_mushroom_hill: bool,
+    #[default = false]
+    /// Flying Battery Zone boss
+    pub boss_flying_battery: bool,
+    #[default = false]
+    /// Sandopolis Zone boss
+    pub boss_sandopolis: bool,
+    #[default = false]
+    /// Lava Reef Zone boss
+    pub boss_lava_reef: bool,
+    #[default = false]
+    /// Hidden Palace Zone boss
+    pub boss_hidden_palace: bool,
+    #[default = false]
+    /// Sky Sanctuary Zone boss
+    pub boss_sky_sanctuary: bool,
+    #[default = false]
+    /// Mecha Sonic, Knuckles' last boss in Sky Sanctuary Zone
+    pub boss_mecha_sonic: bool,
+    #[default = false]
+    /// Death Egg Zone boss
+    pub boss_death_egg: bool,
+    #[default = false]
+    /// Doomsday Zone boss
+    pub boss_doomsday: bool,
+    #[default = false]
+    /// All Bosses: split on every boss and mini-boss defeat, in place of every other split
+    pub all_bosses: bool,
+    #[default = false]
+    /// Split when the Death Egg launch cutscene starts in Launch Base Zone - Act 2
+    pub death_egg_launch: bool,
+    #[heading_level = 0]
+    /// Other splits
+    _other_splits: Title,
+    /// Split after completing special stages
+    pub special_stages: SpecialStageSplit,
+    #[default = false]
+    /// Split when the seventh Chaos Emerald is collected
+    pub all_chaos_emeralds: bool,
+    /// Split on Super Emeralds being collected
+    pub super_emeralds: SuperEmeraldSplit,
+    #[default = false]
+    /// Split when a Perfect bonus is awarded at the act tally
+    pub perfect_bonus: bool,
+    #[default = false]
+    /// Split on the first Super transformation of the run
+    pub super_transformation: bool,
+    #[default = false]
+    /// Split on the first Hyper transformation of the run
+    pub hyper_transformation: bool,
+    #[heading_level = 0]
+    /// Game time
+    _game_time: Title,
+    /// What game time measures
+    ///
+    /// Splits detected a few frames after their trigger are only backdated to it when counting
+    /// frames, as real time and the in-game timers can't tell when the trigger happened.
+    pub game_time_source: GameTimeSource,
+    #[default = false]
+    /// Remove loads (level loading, data select fade out, blackout between acts)
+    pub remove_loads: bool,
+    #[default = false]
+    /// Pause game time in special stages, up to the end of their results screen
+    pub pause_in_special_stages: bool,
+    #[default = false]
+    /// Pause game time in the star post bonus stages (Gumball Machine, Glowing Spheres, Slot Machine)
+    pub pause_in_bonus_stages: bool,
+    #[default = false]
+    /// RTA-TB: pause game time while the time bonus is counted down at the tally
+    pub pause_during_time_bonus: bool,
+    /// While the game is paused from its pause menu
+    pub pause_menu: PauseMenuPause,
+    #[default = false]
+    /// Pause game time while the game is frozen (suspended, emulator paused, breakpoint)
+    pub pause_when_suspended: bool,
+    #[default = false]
+    /// Pause game time while the game is closed mid-run, until it's running again
+    pub pause_when_closed: bool,
+    #[default = false]
+    /// Pause game time once the game has been frozen for 10 seconds (hung or crashed)
+    pub pause_when_hung: bool,
+    #[default = false]
+    /// Pause game time in the data select screen instead of resetting when re-entering the save
+    pub menu_return_pauses: bool,
+    #[default = false]
+    /// Pause game time on the data select screen after the first split
+    pub pause_on_data_select: bool,
+    #[default = false]
+    /// Don't count the Angel Island intro, until the player gains control
+    pub exclude_intro: bool,
+    #[heading_level = 0]
+    /// Competition
+    _competition: Title,
+    #[default = false]
+    /// Auto start when a race starts
+    pub competition_start: bool,
+    #[default = true]
+    /// Split when a race is completed
+    pub competition_split: bool,
+    #[default = true]
+    /// Auto reset when returning to the competition menu
+    pub competition_reset: bool,
+    #[heading_level = 0]
+    /// Multiple instances
+    _multiple_instances: Title,
+    #[default = false]
+    /// Follow a second instance of the game, exposing both players' progress as variables
+    pub dual_instances: bool,
+    /// Instance of the game driving the timer when following two of them
+    pub timer_instance: TimerInstance,
+    #[heading_level = 0]
+    /// Safeguards
+    _safeguards: Title,
+    #[default = true]
+    /// Hold off timer decisions while A.I.R.'s options or extras overlay is open
+    pub hold_during_overlay: bool,
+    #[default = true]
+    /// Only log timer decisions while practice tools (savestates, debug mode) are in use
+    pub suppress_practice: bool,
+    /// Compatibility with script mods adding characters or levels
+    pub mod_compatibility: ModCompatibility,
+    #[heading_level = 0]
+    /// Debug
+    _debug: Title,
+    #[default = false]
+    /// Log state transitions and timer decisions
+    pub debug_logging: bool,
+    #[default = false]
+    /// Dry run (log timer decisions without controlling the timer)
+    pub dry_run: bool,
+    #[default = false]
+    /// Log the recent transition history (toggle on to dump)
+    pub dump_history: bool,
+    #[default = false]
+    /// Run a self-check of every watched value (toggle on to run)
+    pub self_check: bool,
+}
+
+impl Settings {
+    /// Loads the current settings, with the category preset applied over them
+    pub fn refresh(&mut self) {
+        self.update();
+
+        let (time_attack, sonic_3_alone, sonic_and_knuckles_alone) = match self.category {
+            Category::Custom => return,
+            Category::BeatTheGame | Category::AllEmeralds => (false, false, false),
+            Category::Sonic3Alone => (false, true, false),
+            Category::SonicAndKnucklesAlone => (false, false, true),
+            Category::IndividualLevels => (true, false, false),
+        };
+        self.time_attack = time_attack;
+        self.sonic_3_alone = sonic_3_alone;
+        self.sonic_and_knuckles_alone = sonic_and_knuckles_alone;
+
+        // Full game categories follow the character's route, and only the emerald categories end
+        // on the last emerald
+        self.character_presets = !time_attack;
+        self.all_chaos_emeralds = self.category == Category::AllEmeralds;
+    }
+
+    /// Whether completing the given act is set to trigger a split in a run with the given character,
+    /// holding the given number of emeralds. The character presets enable the acts that are part
+    /// of the character's story, and disable the ones off the character's route.
+    pub const fn act_split_enabled_for(
+        &self,
+        level: Levels,
+        character: Character,
+        emeralds: u8,
+    ) -> bool {
+        // Acts off the character's route are never reached, in Sonic & Knuckles alone too
+        if (self.character_presets || self.sonic_and_knuckles_alone)
+            && !character.plays(level, emeralds)
+            && !matches!(level, Levels::Ending)
+        {
+            return false;
+        }
+        if self.character_presets {
+            // Hidden Palace ends with a boss fight for Sonic
+            if matches!(character, Character::Sonic | Character::SonicAndTails)
+                && matches!(level, Levels::HiddenPalace)
+            {
+                return true;
+            }
+        }
+        self.act_split_enabled(level)
+    }
+
+    /// Whether defeating a boss in the given act is set to trigger a split. Only the last act of
+    /// each zone has a setting, as that's where the zone's boss is fought.
+    pub const fn boss_split_enabled(&self, level: Levels) -> bool {
+        match level {
+            Levels::AngelIslandAct2 => self.boss_angel_island,
+            Levels::HydrocityAct2 => self.boss_hydrocity,
+            Levels::MarbleGardenAct2 => self.boss_marble_garden,
+            Levels::CarnivalNightAct2 => self.boss_carnival_night,
+            Levels::IceCapAct2 => self.boss_ice_cap,
+            Levels::LaunchBaseAct2 => self.boss_launch_base,
+            Levels::MushroomHillAct2 => self.boss_mushroom_hill,
+            Levels::FlyingBatteryAct2 => self.boss_flying_battery,
+            Levels::SandopolisAct2 => self.boss_sandopolis,
+            Levels::LavaReefAct2 => self.boss_lava_reef,
+            Levels::HiddenPalace => self.boss_hidden_palace,
+            Levels::SkySanctuary => self.boss_sky_sanctuary,
+            Levels::DeathEggAct2 => self.boss_death_egg,
+            Levels::DoomsDay => self.boss_doomsday,
+            _ => false,
+        }
+    }
+
+    /// Whether completing the given act is set to trigger a split
+    pub const fn act_split_enabled(&self, level: Levels) -> bool {
+        if self.zone_splits && level.is_first_act() {
+            return false;
+        }
+
+        if self.sonic_and_knuckles_alone && (level as u8) < Levels::MushroomHillAct1 as u8 {
+            return false;
+        }
+
+        // Launch Base Zone - Act 2 itself ends the run with Big Arm, before the act is left
+        if self.sonic_3_alone && level as u8 >= Levels::LaunchBaseAct2 as u8 {
+            return false;
+        }
+
+        match level {
+            Levels::AngelIslandAct1 => self.angel_island_1,
+            Levels::AngelIslandAct2 => self.angel_island_2,
+            Levels::HydrocityAct1 => self.hydrocity_1,
+            Levels::HydrocityAct2 => self.hydrocity_2,
+            Levels::MarbleGardenAct1 => self.marble_garden_1,
+            Levels::MarbleGardenAct2 => self.marble_garden_2,
+            Levels::CarnivalNightAct1 => self.carnival_night_1,
+            Levels::CarnivalNightAct2 => self.carnival_night_2,
+            Levels::IceCapAct1 => self.ice_cap_1,
+            Levels::IceCapAct2 => self.ice_cap_2,
+            Levels::LaunchBaseAct1 => self.launch_base_1,
+            Levels::LaunchBaseAct2 => self.launch_base_2,
+            Levels::MushroomHillAct1 => self.mushroom_hill_1,
+            Levels::MushroomHillAct2 => self.mushroom_hill_2,
+            Levels::FlyingBatteryAct1 => self.flying_battery_1,
+            Levels::FlyingBatteryAct2 => self.flying_battery_2,
+            Levels::SandopolisAct1 => self.sandopolis_1,
+            Levels::SandopolisAct2 => self.sandopolis_2,
+            Levels::LavaReefAct1 => self.lava_reef_1,
+            Levels::LavaReefAct2 => self.lava_reef_2,
+            Levels::HiddenPalace => self.hidden_palace,
+            Levels::SkySanctuary => self.sky_sanctuary,
+            Levels::DeathEggAct1 => self.death_egg_1,
+            Levels::DeathEggAct2 => self.death_egg_2,
+            Levels::DoomsDay => self.doomsday,
+            Levels::Ending => self.ending,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Custom (use the options below as they are)
+    #[default]
+    Custom,
+    /// Beat the Game
+    BeatTheGame,
+    /// All Emeralds
+    AllEmeralds,
+    /// Sonic 3 alone
+    Sonic3Alone,
+    /// Sonic & Knuckles alone
+    SonicAndKnucklesAlone,
+    /// Individual Levels (Time Attack)
+    IndividualLevels,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialStageSplit {
+    /// Disabled
+    #[default]
+    Disabled,
+    /// Every special stage
+    Every1,
+    /// Every 2 special stages
+    Every2,
+    /// Every 3 special stages
+    Every3,
+    /// Every 4 special stages
+    Every4,
+    /// Every 7 special stages
+    Every7,
+}
+
+impl SpecialStageSplit {
+    pub const fn interval(self) -> u32 {
+        match self {
+            Self::Disabled => 0,
+            Self::Every1 => 1,
+            Self::Every2 => 2,
+            Self::Every3 => 3,
+            Self::Every4 => 4,
+            Self::Every7 => 7,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum SuperEmeraldSplit {
+    /// Disabled
+    #[default]
+    Disabled,
+    /// Every Super Emerald
+    Every,
+    /// Only the last one (14th emerald)
+    Last,
+}
+
+impl SuperEmeraldSplit {
+    /// Whether collecting the given Super Emerald, counted from 1, is set to trigger a split
+    pub const fn applies_to(self, super_emeralds: u8) -> bool {
+        match self {
+            Self::Disabled => false,
+            Self::Every => true,
+            Self::Last => super_emeralds >= 7,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum DoomsdaySplit {
+    /// When the ending starts
+    #[default]
+    Ending,
+    /// On the last hit to the final boss
+    BossDefeated,
+    /// When the screen fades out after the final boss
+    FadeOut,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMinimumTime {
+    /// Never
+    #[default]
+    Never,
+    /// 30 seconds
+    Seconds30,
+    /// 1 minute
+    Minute1,
+    /// 5 minutes
+    Minutes5,
+    /// 15 minutes
+    Minutes15,
+}
+
+impl ResetMinimumTime {
+    /// The duration in frames, at the given frame rate
+    pub const fn frames(self, frame_rate: u32) -> u32 {
+        match self {
+            Self::Never => u32::MAX,
+            Self::Seconds30 => 30 * frame_rate,
+            Self::Minute1 => 60 * frame_rate,
+            Self::Minutes5 => 5 * 60 * frame_rate,
+            Self::Minutes15 => 15 * 60 * frame_rate,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum StartCharacter {
+    /// Any character
+    #[default]
+    Any,
+    /// Sonic & Tails
+    SonicAndTails,
+    /// Sonic alone
+    Sonic,
+    /// Tails alone
+    Tails,
+    /// Knuckles
+    Knuckles,
+}
+
+impl StartCharacter {
+    pub fn allows(self, character: Character) -> bool {
+        match self {
+            Self::Any => true,
+            Self::SonicAndTails => character == Character::SonicAndTails,
+            Self::Sonic => character == Character::Sonic,
+            Self::Tails => character == Character::Tails,
+            Self::Knuckles => character == Character::Knuckles,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum StartTiming {
+    /// As the game starts loading
+    #[default]
+    Load,
+    /// Once the player gains control
+    Control,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum SaveSlotFilter {
+    /// Any save slot
+    #[default]
+    Any,
+    /// No save
+    NoSave,
+    /// Save slot 1
+    Slot1,
+    /// Save slot 2
+    Slot2,
+    /// Save slot 3
+    Slot3,
+    /// Save slot 4
+    Slot4,
+    /// Save slot 5
+    Slot5,
+    /// Save slot 6
+    Slot6,
+    /// Save slot 7
+    Slot7,
+    /// Save slot 8
+    Slot8,
+}
+
+// The settings widget needs its variants spelled out, one per save slot of the data select screen
+const _: () = assert!(SAVE_SLOTS == 8);
+
+impl SaveSlotFilter {
+    /// Whether the save select value, 0 being no save, is the chosen slot
+    pub const fn allows(self, save_select: u8) -> bool {
+        let slot = match self {
+            Self::Any => return true,
+            Self::NoSave => 0,
+            Self::Slot1 => 1,
+            Self::Slot2 => 2,
+            Self::Slot3 => 3,
+            Self::Slot4 => 4,
+            Self::Slot5 => 5,
+            Self::Slot6 => 6,
+            Self::Slot7 => 7,
+            Self::Slot8 => 8,
+        };
+        save_select == slot
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum GameTimeSource {
+    /// Real time, minus the pauses below
+    #[default]
+    RealTime,
+    /// In-game timer of the current act
+    ActIgt,
+    /// Sum of every act's in-game timer, score tallies excluded
+    CumulativeIgt,
+    /// Frames played, minus the pauses below, at the measured simulation rate
+    Frames,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuPause {
+    /// Keep timing
+    #[default]
+    Disabled,
+    /// Pause game time
+    GameTime,
+    /// Pause the timer itself
+    Timer,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum ModCompatibility {
+    /// When a mod is detected
+    #[default]
+    Auto,
+    /// Never (the game is unmodded)
+    Never,
+    /// Always (mods the splitter can't detect are installed)
+    Always,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+pub enum TimerInstance {
+    /// First instance
+    #[default]
+    First,
+    /// Second instance
+    Second,
+}
+
+impl TimerInstance {
+    /// Position of the instance driving the timer, and of the other one
+    pub const fn instances(self) -> (usize, usize) {
+        match self {
+            Self::First => (0, 1),
+            Self::Second => (1, 0),
+        }
+    }
+}